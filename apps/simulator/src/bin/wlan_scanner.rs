@@ -0,0 +1,21 @@
+use anyhow::Error;
+use ratatui_mousefood_runtime::clock::{Clock, SystemClock};
+use ratatui_mousefood_runtime::storage::{shared, FileStorage, Namespace};
+use ratatui_wlan_scanner::app::AppState;
+use ratatui_wlan_scanner::scan_log::{ScanLog, DEFAULT_LOG_CAPACITY};
+use ratatui_wlan_scanner::wifi::MockScanner;
+
+fn main() -> Result<(), Error> {
+    let storage = shared(FileStorage::new("simulator-storage"));
+    let log = ScanLog::open(
+        Namespace::new(&storage, "scan_log"),
+        DEFAULT_LOG_CAPACITY,
+        SystemClock.now(),
+    )?;
+    let app = AppState::new()
+        .with_settings(Namespace::new(&storage, "settings"))
+        .with_scan_log(log)
+        .with_console()
+        .with_scanner(|| Ok(MockScanner::new()));
+    simulator::run(app)
+}
@@ -0,0 +1,198 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay, SimulatorEvent, Window};
+use mousefood::embedded_graphics::geometry;
+use mousefood::embedded_graphics::prelude::{
+    Dimensions, DrawTarget, OriginDimensions, Pixel, PointsIter, RgbColor,
+};
+use mousefood::prelude::*;
+
+use anyhow::Error;
+use mousefood::ratatui::Terminal;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::{ButtonEdge, ButtonId};
+use ratatui_mousefood_runtime::clock::{Clock, SystemClock};
+use ratatui_mousefood_runtime::event_loop::{EventLoop, Platform};
+use ratatui_mousefood_runtime::DISPLAY_SIZE;
+
+/// Time between two checks for window events while waiting for a key.
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Keys that are currently held down in the simulator window.
+#[derive(Debug, Default, Clone, Copy)]
+struct Keys {
+    button1: bool,
+    button2: bool,
+    both: bool,
+}
+
+impl Keys {
+    /// Update the key state from an SDL key name.
+    ///
+    /// - `1` or `Left` acts as button 1.
+    /// - `2` or `Right` acts as button 2.
+    /// - `Space` holds both buttons at once.
+    fn set(&mut self, key_name: &str, pressed: bool) {
+        match key_name {
+            "1" | "Left" => self.button1 = pressed,
+            "2" | "Right" => self.button2 = pressed,
+            "Space" => self.both = pressed,
+            _ => {}
+        }
+    }
+
+    /// Check if a button is held down by its keys.
+    fn is_pressed(&self, button: ButtonId) -> bool {
+        match button {
+            ButtonId::Button1 => self.button1 || self.both,
+            ButtonId::Button2 => self.button2 || self.both,
+        }
+    }
+}
+
+/// Simulated T-Display buttons, driven by the keyboard, and backlight.
+struct Simulator {
+    window: Rc<RefCell<Window>>,
+    keys: Keys,
+    /// Edges of the buttons that were not returned yet
+    edges: VecDeque<ButtonEdge>,
+    brightness: Rc<Cell<u8>>,
+}
+
+impl Simulator {
+    /// Update the keys from the window events, and queue an edge for every button they change.
+    ///
+    /// The window only has events after it was drawn once, which the event loop does before it
+    /// waits for the buttons.
+    fn handle_events(&mut self) {
+        let now = self.now();
+        for event in self.window.borrow_mut().events() {
+            let (keycode, pressed) = match event {
+                SimulatorEvent::Quit => panic!("simulator window closed"),
+                SimulatorEvent::KeyDown { keycode, .. } => (keycode, true),
+                SimulatorEvent::KeyUp { keycode, .. } => (keycode, false),
+                _ => continue,
+            };
+            let before = self.keys;
+            self.keys.set(&keycode.name(), pressed);
+            for button in [ButtonId::Button1, ButtonId::Button2] {
+                if before.is_pressed(button) != self.keys.is_pressed(button) {
+                    self.edges.push_back(ButtonEdge {
+                        button,
+                        pressed: self.keys.is_pressed(button),
+                        at: now,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Clock for Simulator {
+    fn now(&self) -> Instant {
+        SystemClock.now()
+    }
+}
+
+impl Platform for Simulator {
+    fn next_edge(&mut self, timeout: Duration) -> Option<ButtonEdge> {
+        let deadline = self.now() + timeout;
+        loop {
+            self.handle_events();
+            if let Some(edge) = self.edges.pop_front() {
+                return Some(edge);
+            }
+            let now = self.now();
+            if now >= deadline {
+                return None;
+            }
+            thread::sleep(EVENT_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    fn set_backlight(&mut self, brightness: u8) {
+        self.brightness.set(brightness.min(100));
+    }
+}
+
+/// Copy of the display with every pixel dimmed to `brightness` percent, like the backlight
+/// does on the device.
+fn dimmed(display: &SimulatorDisplay<Bgr565>, brightness: u8) -> SimulatorDisplay<Bgr565> {
+    let mut dimmed = SimulatorDisplay::new(display.size());
+    let scale = |channel: u8| (u16::from(channel) * u16::from(brightness) / 100) as u8;
+    let pixels = display.bounding_box().points().map(|point| {
+        let color = display.get_pixel(point);
+        Pixel(
+            point,
+            Bgr565::new(scale(color.r()), scale(color.g()), scale(color.b())),
+        )
+    });
+    dimmed.draw_iter(pixels).unwrap();
+    dimmed
+}
+
+/// Run the application inside the simulator window.
+///
+/// The window has the same size and orientation as the T-Display. Holding a key acts like
+/// holding the matching button, so short and long presses are detected exactly like on the
+/// device:
+///
+/// - `1` or `Left`: button 1
+/// - `2` or `Right`: button 2
+/// - `Space` (or both keys together): both buttons
+///
+/// This function returns once the app returns `Command::Quit`, or if rendering fails. Closing
+/// the window ends the process.
+pub fn run(mut app: impl App) -> Result<(), Error> {
+    // Create window where the simulation will happen
+    let window = Rc::new(RefCell::new(Window::new(
+        "mousefood simulator",
+        &OutputSettings {
+            scale: 3,
+            max_fps: 30,
+            ..Default::default()
+        },
+    )));
+
+    // The display is rotated by 90°, so width and height are swapped
+    let mut display = SimulatorDisplay::<Bgr565>::new(geometry::Size::new(
+        DISPLAY_SIZE.1.into(),
+        DISPLAY_SIZE.0.into(),
+    ));
+
+    let simulator_window = Rc::clone(&window);
+    let brightness = Rc::new(Cell::new(100));
+    let window_brightness = Rc::clone(&brightness);
+
+    let backend_config = EmbeddedBackendConfig {
+        // Define how to display newly rendered widgets to the simulator window
+        flush_callback: Box::new(move |display| {
+            let mut simulator_window = simulator_window.borrow_mut();
+            match window_brightness.get() {
+                100 => simulator_window.update(display),
+                brightness => simulator_window.update(&dimmed(display, brightness)),
+            }
+        }),
+        ..Default::default()
+    };
+    let backend: EmbeddedBackend<SimulatorDisplay<_>, _> =
+        EmbeddedBackend::new(&mut display, backend_config);
+
+    // Start ratatui with our simulator backend
+    let mut terminal = Terminal::new(backend)?;
+
+    // Run the same event loop as on the device
+    let simulator = Simulator {
+        window,
+        keys: Keys::default(),
+        edges: VecDeque::new(),
+        brightness,
+    };
+    EventLoop::new(simulator).run(&mut app, &mut terminal)?;
+
+    Ok(())
+}
@@ -1,52 +1,55 @@
-use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay, SimulatorEvent, Window};
-use mousefood::embedded_graphics::geometry;
+use std::time::Duration;
+
 use mousefood::prelude::*;
 
 use anyhow::Error;
 use mousefood::ratatui::widgets::{Block, Paragraph, Wrap};
-use mousefood::ratatui::{Frame, Terminal};
-
-fn draw(frame: &mut Frame) {
-    let text = "Ratatui on embedded devices!";
-    let paragraph = Paragraph::new(text.dark_gray()).wrap(Wrap { trim: true });
-    let bordered_block = Block::bordered()
-        .border_style(Style::new().yellow())
-        .title("Mousefood");
-    frame.render_widget(paragraph.block(bordered_block), frame.area());
+use mousefood::ratatui::Frame;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::{Button, ButtonPressType};
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::timer::{Timer, TimerId};
+
+/// Timer that clears the last pressed button.
+const CLEAR_BUTTON: TimerId = TimerId(0);
+
+/// Demo application rendered by the simulator.
+#[derive(Default)]
+struct Demo {
+    /// Tracks the last button that was pressed.
+    button_pressed: Option<Button>,
 }
 
-fn main() -> Result<(), Error> {
-    // Create window where the simulation will happen
-    let mut simulator_window = Window::new(
-        "mousefood simulator",
-        &OutputSettings {
-            scale: 4,
-            max_fps: 30,
-            ..Default::default()
-        },
-    );
-
-    // Define properties of the display which will be shown in the simulator window
-    let mut display = SimulatorDisplay::<Bgr565>::new(geometry::Size::new(128, 64));
-
-    let backend_config = EmbeddedBackendConfig {
-        // Define how to display newly rendered widgets to the simulator window
-        flush_callback: Box::new(move |display| {
-            simulator_window.update(display);
-            if simulator_window.events().any(|e| e == SimulatorEvent::Quit) {
-                panic!("simulator window closed");
-            }
-        }),
-        ..Default::default()
-    };
-    let backend: EmbeddedBackend<SimulatorDisplay<_>, _> =
-        EmbeddedBackend::new(&mut display, backend_config);
-
-    // Start ratatui with our simulator backend
-    let mut terminal = Terminal::new(backend)?;
-
-    // Run an infinite loop, where widgets will be rendered
-    loop {
-        terminal.draw(draw)?;
+impl App for Demo {
+    fn draw(&self, frame: &mut Frame) {
+        let text = match self.button_pressed {
+            Some(button) => format!("You pressed: {button}"),
+            None => "Ratatui on embedded devices!".to_string(),
+        };
+        let paragraph = Paragraph::new(text.dark_gray()).wrap(Wrap { trim: true });
+        let bordered_block = Block::bordered()
+            .border_style(Style::new().yellow())
+            .title("Mousefood");
+        frame.render_widget(paragraph.block(bordered_block), frame.area());
+    }
+
+    fn handle_press(&mut self, button: Button) -> Vec<Command> {
+        if button == Button::Both(ButtonPressType::Hold) {
+            return vec![Command::Quit];
+        }
+        self.button_pressed = Some(button);
+        vec![
+            Command::Redraw,
+            Command::StartTimer(CLEAR_BUTTON, Timer::Once(Duration::from_secs(3))),
+        ]
     }
+
+    fn on_timer(&mut self, _id: TimerId) -> Vec<Command> {
+        self.button_pressed = None;
+        vec![Command::Redraw]
+    }
+}
+
+fn main() -> Result<(), Error> {
+    simulator::run(Demo::default())
 }
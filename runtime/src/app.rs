@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use crate::backlight::BacklightConfig;
+use crate::button::{Button, ButtonConfig, ButtonEvent, ButtonId};
+use crate::command::Command;
+use crate::frame::{FrameConfig, FrameStats};
+use crate::storage::{Namespace, StorageError};
+use crate::timer::TimerId;
+use mousefood::prelude::*;
+
+/// State of the event loop, passed to [`App::update`].
+#[derive(Debug, Clone, Copy)]
+pub struct Context {
+    /// Time of the update, from the clock of the platform.
+    pub now: Instant,
+    /// Backlight brightness in percent.
+    pub brightness: u8,
+    /// Measured times of the frames drawn so far.
+    pub frame_stats: FrameStats,
+}
+
+/// Application trait to be implemented by the user.
+pub trait App {
+    /// Draw the UI frame.
+    ///
+    /// Only called when the app changed, i.e. after it returned [`Command::Redraw`], and at
+    /// most [`FrameConfig::max_fps`] times per second.
+    fn draw(&self, frame: &mut Frame);
+
+    /// Handle button press events.
+    ///
+    /// Returns the commands for the event loop, e.g. [`Command::Redraw`] if the app changed.
+    fn handle_press(&mut self, button: Button) -> Vec<Command>;
+
+    /// Handle a button going down or up, and the progress of a held button.
+    ///
+    /// Called right away, before the press is detected, e.g. to show when a long press will
+    /// register. [`ButtonEvent::HoldProgress`] is sent once per event loop iteration while a
+    /// button is held. Returns the commands for the event loop, e.g. [`Command::Redraw`] if the
+    /// app changed. Does nothing by default.
+    fn handle_event(&mut self, _event: ButtonEvent) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Do work that does not depend on a button press, e.g. handle messages from other threads
+    /// or advance an animation by `dt`, the time since the last update.
+    ///
+    /// Called every [`FrameConfig::tick_interval`], before drawing. Returns the commands for the
+    /// event loop, e.g. [`Command::Redraw`] if the app changed. Does nothing by default.
+    fn update(&mut self, _ctx: &Context, _dt: Duration) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Handle a timer that was started with [`Command::StartTimer`].
+    ///
+    /// Called before drawing once the timer is due, and again every interval for a repeating
+    /// timer. Returns the commands for the event loop. Does nothing by default.
+    fn on_timer(&mut self, _id: TimerId) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Handle a [`Command::Persist`] that failed, e.g. since the flash is full.
+    ///
+    /// The event loop keeps running, and the changes stay staged in the namespace. Returns the
+    /// commands for the event loop, e.g. to show the error. Logs the error to stderr by default,
+    /// which is the serial console on the device.
+    fn on_persist_error(&mut self, namespace: &Namespace, error: StorageError) -> Vec<Command> {
+        eprintln!("Failed to persist {}: {error}", namespace.name());
+        Vec::new()
+    }
+
+    /// Get the backlight brightness, and when to dim and turn it off while the buttons are idle.
+    ///
+    /// Called once per event loop iteration. Returns [`BacklightConfig::default`] by default.
+    fn backlight(&self) -> BacklightConfig {
+        BacklightConfig::default()
+    }
+
+    /// Get the debounce time and the press durations of a button.
+    ///
+    /// Called for every button edge. Returns [`ButtonConfig::default`] by default.
+    fn button_config(&self, _button: ButtonId) -> ButtonConfig {
+        ButtonConfig::default()
+    }
+
+    /// Get the maximum frame rate, and how often the app is updated.
+    ///
+    /// Called once per event loop iteration. Returns [`FrameConfig::default`] by default.
+    fn frame_config(&self) -> FrameConfig {
+        FrameConfig::default()
+    }
+
+    /// Run the application on the ESP32 T-Display.
+    ///
+    /// Default implementation provided. Do not override unless necessary.
+    #[cfg(feature = "esp")]
+    fn run(self)
+    where
+        Self: Sized,
+    {
+        crate::esp::run_app(self);
+    }
+}
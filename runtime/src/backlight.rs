@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+/// Backlight brightness of an app, and when to dim and turn it off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacklightConfig {
+    /// Brightness in percent while buttons are used.
+    pub brightness: u8,
+    /// Brightness in percent once the buttons were idle for [`Self::dim_after`].
+    pub dim_brightness: u8,
+    /// Time without a button press before the backlight is dimmed, `None` to never dim.
+    pub dim_after: Option<Duration>,
+    /// Time without a button press before the backlight is turned off, `None` to never turn
+    /// it off.
+    pub off_after: Option<Duration>,
+}
+
+impl Default for BacklightConfig {
+    fn default() -> Self {
+        Self {
+            brightness: 100,
+            dim_brightness: 10,
+            dim_after: Some(Duration::from_secs(30)),
+            off_after: Some(Duration::from_secs(120)),
+        }
+    }
+}
+
+/// Backlight that dims and turns off while the buttons are idle.
+#[derive(Debug)]
+pub struct IdleBacklight {
+    last_activity: Instant,
+    /// Whether the backlight was turned off until the next button activity
+    asleep: bool,
+}
+
+impl IdleBacklight {
+    /// Create a backlight whose buttons were last used at `now`.
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_activity: now,
+            asleep: false,
+        }
+    }
+
+    /// Record button activity at `now`, which restores the full brightness.
+    pub fn wake(&mut self, now: Instant) {
+        self.last_activity = now;
+        self.asleep = false;
+    }
+
+    /// Turn the backlight off right away, until the next button activity.
+    pub fn sleep(&mut self) {
+        self.asleep = true;
+    }
+
+    /// Check if the backlight was turned off until the next button activity.
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// Check if the backlight is off at `now`.
+    pub fn is_off(&self, config: &BacklightConfig, now: Instant) -> bool {
+        self.brightness(config, now) == 0
+    }
+
+    /// Brightness in percent at `now`.
+    pub fn brightness(&self, config: &BacklightConfig, now: Instant) -> u8 {
+        let idle = now.duration_since(self.last_activity);
+        let brightness = config.brightness.min(100);
+        if self.asleep || config.off_after.is_some_and(|off_after| idle >= off_after) {
+            0
+        } else if config.dim_after.is_some_and(|dim_after| idle >= dim_after) {
+            config.dim_brightness.min(brightness)
+        } else {
+            brightness
+        }
+    }
+}
@@ -0,0 +1,492 @@
+use core::fmt;
+use std::mem;
+use std::time::{Duration, Instant};
+
+/// Type of button press: short, long, held for a very long time, two short ones in a row, or
+/// repeated while held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonPressType {
+    Short,
+    Long,
+    Hold,
+    Double,
+    Repeat,
+}
+
+impl fmt::Display for ButtonPressType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ButtonPressType::Short => write!(f, "Short Press"),
+            ButtonPressType::Long => write!(f, "Long Press"),
+            ButtonPressType::Hold => write!(f, "Hold"),
+            ButtonPressType::Double => write!(f, "Double Press"),
+            ButtonPressType::Repeat => write!(f, "Repeat"),
+        }
+    }
+}
+
+/// Timing of the presses of a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonConfig {
+    /// Time after an edge during which further edges are ignored as contact bounce.
+    pub debounce: Duration,
+    /// Shortest press that is a [`ButtonPressType::Long`] press.
+    pub long_press: Duration,
+    /// Shortest press that is a [`ButtonPressType::Hold`] instead of a long press.
+    pub hold: Duration,
+    /// Longest time between two short presses that are a [`ButtonPressType::Double`] press,
+    /// `None` to not detect them.
+    ///
+    /// A short press is only reported once this time passed without a second one.
+    pub double_press: Option<Duration>,
+    /// Auto-repeat while the button is held, `None` to not repeat.
+    pub repeat: Option<RepeatConfig>,
+}
+
+impl Default for ButtonConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(20),
+            long_press: Duration::from_millis(500),
+            hold: Duration::from_millis(2000),
+            double_press: None,
+            repeat: None,
+        }
+    }
+}
+
+/// Timing of the [`ButtonPressType::Repeat`] presses while a button is held.
+///
+/// The repeats get faster the longer the button is held: every interval is
+/// [`Self::acceleration`] percent of the one before, down to [`Self::min_interval`]. A press
+/// that repeated is not reported again when the button is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatConfig {
+    /// Time the button has to be held for the first repeat.
+    pub delay: Duration,
+    /// Time between the first and the second repeat.
+    pub interval: Duration,
+    /// Shortest time between two repeats.
+    pub min_interval: Duration,
+    /// Length of every interval in percent of the one before.
+    pub acceleration: u8,
+}
+
+impl Default for RepeatConfig {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(200),
+            min_interval: Duration::from_millis(40),
+            acceleration: 80,
+        }
+    }
+}
+
+impl RepeatConfig {
+    /// Get the interval after one of `interval`.
+    fn next_interval(&self, interval: Duration) -> Duration {
+        (interval * u32::from(self.acceleration.min(100)) / 100)
+            .max(self.min_interval)
+            .max(Duration::from_millis(1))
+    }
+}
+
+impl ButtonConfig {
+    /// Get the type of a press that lasted for `duration`.
+    pub fn press_type(&self, duration: Duration) -> ButtonPressType {
+        if duration >= self.hold {
+            ButtonPressType::Hold
+        } else if duration >= self.long_press {
+            ButtonPressType::Long
+        } else {
+            ButtonPressType::Short
+        }
+    }
+}
+
+/// Longest time between the presses of the two buttons of a [`Button::Both`] chord.
+///
+/// If the second button is pressed later, the chord is ordered, e.g. [`Button::Button1Then2`].
+pub const SIMULTANEOUS_PRESS: Duration = Duration::from_millis(100);
+
+/// Button enum representing different button actions.
+///
+/// Chords of both buttons are reported once, when the first of them is released, with the
+/// time both were held as press type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Button1(ButtonPressType),
+    Button2(ButtonPressType),
+    /// Both buttons pressed at the same time.
+    Both(ButtonPressType),
+    /// Button 1 held, then button 2 pressed.
+    Button1Then2(ButtonPressType),
+    /// Button 2 held, then button 1 pressed.
+    Button2Then1(ButtonPressType),
+}
+
+impl fmt::Display for Button {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Button::Button1(press_type) => write!(f, "Button 1 ({press_type})"),
+            Button::Button2(press_type) => write!(f, "Button 2 ({press_type})"),
+            Button::Both(press_type) => write!(f, "Both Buttons ({press_type})"),
+            Button::Button1Then2(press_type) => write!(f, "Button 1 + 2 ({press_type})"),
+            Button::Button2Then1(press_type) => write!(f, "Button 2 + 1 ({press_type})"),
+        }
+    }
+}
+
+impl Button {
+    /// Check if the button is [`Button::Button1`].
+    pub fn is_button1(&self) -> bool {
+        matches!(self, Button::Button1(_))
+    }
+
+    /// Check if the button is [`Button::Button2`].
+    pub fn is_button2(&self) -> bool {
+        matches!(self, Button::Button2(_))
+    }
+
+    /// Check if the button is a chord of both buttons, in any order.
+    pub fn is_chord(&self) -> bool {
+        matches!(
+            self,
+            Button::Both(_) | Button::Button1Then2(_) | Button::Button2Then1(_)
+        )
+    }
+
+    /// Check if a short press was detected.
+    pub fn is_short_press(&self) -> bool {
+        matches!(
+            self,
+            Button::Button1(ButtonPressType::Short) | Button::Button2(ButtonPressType::Short)
+        )
+    }
+
+    /// Check if a long press was detected.
+    pub fn is_long_press(&self) -> bool {
+        matches!(
+            self,
+            Button::Button1(ButtonPressType::Long) | Button::Button2(ButtonPressType::Long)
+        )
+    }
+
+    /// Check if a hold was detected.
+    pub fn is_hold(&self) -> bool {
+        matches!(
+            self,
+            Button::Button1(ButtonPressType::Hold) | Button::Button2(ButtonPressType::Hold)
+        )
+    }
+
+    /// Check if a double press was detected.
+    pub fn is_double_press(&self) -> bool {
+        matches!(
+            self,
+            Button::Button1(ButtonPressType::Double) | Button::Button2(ButtonPressType::Double)
+        )
+    }
+
+    /// Check if this is a repeat of a held button.
+    pub fn is_repeat(&self) -> bool {
+        matches!(
+            self,
+            Button::Button1(ButtonPressType::Repeat) | Button::Button2(ButtonPressType::Repeat)
+        )
+    }
+}
+
+/// One of the two physical buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonId {
+    Button1,
+    Button2,
+}
+
+impl ButtonId {
+    /// Get the press of this button with the given type.
+    pub fn press(self, press_type: ButtonPressType) -> Button {
+        match self {
+            ButtonId::Button1 => Button::Button1(press_type),
+            ButtonId::Button2 => Button::Button2(press_type),
+        }
+    }
+}
+
+/// A button that went down or up, with the time it happened at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonEdge {
+    /// Button that changed.
+    pub button: ButtonId,
+    /// Whether the button went down, or else up.
+    pub pressed: bool,
+    /// Time of the change, which can be before the edge is handled.
+    pub at: Instant,
+}
+
+/// Change of a single button, delivered to the app right away, in addition to the presses
+/// that are detected later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonEvent {
+    /// The button went down, after debouncing.
+    Down(ButtonId),
+    /// The button went up, after debouncing.
+    Up(ButtonId),
+    /// The button is still held, for the given time so far.
+    HoldProgress(ButtonId, Duration),
+}
+
+impl ButtonEvent {
+    /// Get the button that changed.
+    pub fn button(&self) -> ButtonId {
+        match *self {
+            ButtonEvent::Down(button)
+            | ButtonEvent::Up(button)
+            | ButtonEvent::HoldProgress(button, _) => button,
+        }
+    }
+}
+
+/// State of a button, tracking press duration.
+#[derive(Default)]
+pub struct ButtonState {
+    pressed_at: Option<Instant>,
+    /// Time of the last edge that changed the state, to ignore the bounces after it
+    last_change: Option<Instant>,
+    /// Last edge during the debounce time, applied if the button stays that way
+    pending: Option<(bool, Instant)>,
+    /// Release of a short press that is not reported yet, since it can become a double press
+    first_press: Option<Instant>,
+    /// Whether the current press is the second one of a double press
+    second_press: bool,
+    /// Time of the next repeat and the interval after it, while the button is held
+    next_repeat: Option<(Instant, Duration)>,
+    /// Whether the current press was already reported, by a repeat or as part of a chord
+    reported: bool,
+}
+
+impl ButtonState {
+    /// Check if the button is currently held down.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed_at.is_some()
+    }
+
+    /// Get the time the button went down, if it is currently held down.
+    pub fn pressed_at(&self) -> Option<Instant> {
+        self.pressed_at
+    }
+
+    /// Get the time of the last edge that changed the state.
+    pub fn last_change(&self) -> Option<Instant> {
+        self.last_change
+    }
+
+    /// Do not report the current press, e.g. since it is part of a chord.
+    ///
+    /// If it was the second press of a double press, the `on_press` callback is called with
+    /// the first one, which is a short press after all.
+    pub fn cancel<F>(&mut self, mut on_press: F)
+    where
+        F: FnMut(ButtonPressType),
+    {
+        if self.is_pressed() {
+            self.reported = true;
+            if mem::take(&mut self.second_press) {
+                on_press(ButtonPressType::Short);
+            }
+            self.next_repeat = None;
+        }
+    }
+
+    /// Forget a short press that waits for a second one, e.g. since it turned the backlight on.
+    pub fn discard_first_press(&mut self) {
+        self.first_press = None;
+    }
+
+    /// Update the button state with an edge, i.e. whether it is pressed since `at`.
+    ///
+    /// Edges within [`ButtonConfig::debounce`] of the last change are held back until the
+    /// debounce time is over, and only the last of them is applied then. It calls the
+    /// `on_press` callback with every press detected, which is usually when the button is
+    /// released.
+    pub fn update<F>(
+        &mut self,
+        config: &ButtonConfig,
+        is_pressed: bool,
+        at: Instant,
+        mut on_press: F,
+    ) where
+        F: FnMut(ButtonPressType),
+    {
+        self.settle(config, at, &mut on_press);
+        if self.is_bouncing(config, at) {
+            self.pending = Some((is_pressed, at));
+        } else {
+            self.change(config, is_pressed, at, &mut on_press);
+        }
+    }
+
+    /// Report the presses that are due at `now` without an edge: the edge that was held back
+    /// during the debounce time, a short press that did not become a double press, and the
+    /// repeats while the button is held.
+    ///
+    /// Calls the `on_press` callback like [`Self::update`].
+    pub fn settle<F>(&mut self, config: &ButtonConfig, now: Instant, mut on_press: F)
+    where
+        F: FnMut(ButtonPressType),
+    {
+        if !self.is_bouncing(config, now)
+            && let Some((is_pressed, at)) = self.pending.take()
+        {
+            self.change(config, is_pressed, at, &mut on_press);
+        }
+
+        if let Some(released_at) = self.first_press {
+            let window = config.double_press.unwrap_or_default();
+            if now.duration_since(released_at) >= window {
+                self.first_press = None;
+                on_press(ButtonPressType::Short);
+            }
+        }
+
+        while let Some((at, interval)) = self.next_repeat {
+            if at > now {
+                break;
+            }
+            if mem::take(&mut self.second_press) {
+                // The first press was not part of a double press after all
+                on_press(ButtonPressType::Short);
+            }
+            self.reported = true;
+            on_press(ButtonPressType::Repeat);
+            let next_interval = config
+                .repeat
+                .map_or(interval, |repeat| repeat.next_interval(interval));
+            self.next_repeat = Some((at + interval, next_interval));
+        }
+    }
+
+    /// Get the next time [`Self::settle`] has to be called, if any.
+    pub fn deadline(&self, config: &ButtonConfig) -> Option<Instant> {
+        let debounced = self
+            .pending
+            .and(self.last_change)
+            .map(|last_change| last_change + config.debounce);
+        let first_press = self
+            .first_press
+            .map(|released_at| released_at + config.double_press.unwrap_or_default());
+        let repeat = self.next_repeat.map(|(at, _)| at);
+        [debounced, first_press, repeat].into_iter().flatten().min()
+    }
+
+    /// Check if `at` is within the debounce time of the last change.
+    fn is_bouncing(&self, config: &ButtonConfig, at: Instant) -> bool {
+        self.last_change
+            .is_some_and(|last_change| at.duration_since(last_change) < config.debounce)
+    }
+
+    /// Change the state of the button, unless it already is pressed or released.
+    fn change<F>(&mut self, config: &ButtonConfig, is_pressed: bool, at: Instant, on_press: &mut F)
+    where
+        F: FnMut(ButtonPressType),
+    {
+        if is_pressed == self.is_pressed() {
+            return;
+        }
+        self.last_change = Some(at);
+
+        if is_pressed {
+            // Button just went down
+            self.pressed_at = Some(at);
+            if let Some(released_at) = self.first_press.take() {
+                let window = config.double_press.unwrap_or_default();
+                if at.duration_since(released_at) < window {
+                    self.second_press = true;
+                } else {
+                    on_press(ButtonPressType::Short);
+                }
+            }
+            self.next_repeat = config
+                .repeat
+                .map(|repeat| (at + repeat.delay, repeat.interval));
+        } else if let Some(pressed_at) = self.pressed_at.take() {
+            // Button just released
+            self.next_repeat = None;
+            let second_press = mem::take(&mut self.second_press);
+            if mem::take(&mut self.reported) {
+                return;
+            }
+            match config.press_type(at.duration_since(pressed_at)) {
+                ButtonPressType::Short if second_press => on_press(ButtonPressType::Double),
+                ButtonPressType::Short if config.double_press.is_some() => {
+                    self.first_press = Some(at);
+                }
+                press_type => {
+                    if second_press {
+                        on_press(ButtonPressType::Short);
+                    }
+                    on_press(press_type);
+                }
+            }
+        }
+    }
+}
+
+/// State of a chord, i.e. both buttons held down at the same time.
+#[derive(Default)]
+pub struct ChordState {
+    /// Time the chord started, and whether button 1, button 2 or none was held before
+    started: Option<(Instant, Option<ButtonId>)>,
+}
+
+impl ChordState {
+    /// Update the chord after the state of a button changed.
+    ///
+    /// When both buttons are held, the chord starts and the presses of the single buttons are
+    /// canceled. When one of them is released, the chord ends, with a press type from `config`.
+    /// It calls the `on_press` callback with the chord, and before it with a short press that a
+    /// button still had to report when the chord started.
+    pub fn update<F>(
+        &mut self,
+        config: &ButtonConfig,
+        button1: &mut ButtonState,
+        button2: &mut ButtonState,
+        mut on_press: F,
+    ) where
+        F: FnMut(Button),
+    {
+        match (self.started, button1.pressed_at(), button2.pressed_at()) {
+            (None, Some(pressed1), Some(pressed2)) => {
+                let at = pressed1.max(pressed2);
+                let first = if at.duration_since(pressed1.min(pressed2)) <= SIMULTANEOUS_PRESS {
+                    None
+                } else if pressed1 < pressed2 {
+                    Some(ButtonId::Button1)
+                } else {
+                    Some(ButtonId::Button2)
+                };
+                button1.cancel(|press_type| on_press(Button::Button1(press_type)));
+                button2.cancel(|press_type| on_press(Button::Button2(press_type)));
+                self.started = Some((at, first));
+            }
+            (Some((at, first)), None, _) | (Some((at, first)), _, None) => {
+                self.started = None;
+                let released = [&*button1, &*button2]
+                    .into_iter()
+                    .filter(|button| !button.is_pressed())
+                    .filter_map(ButtonState::last_change)
+                    .min()
+                    .unwrap_or(at);
+                let press_type = config.press_type(released.duration_since(at));
+                on_press(match first {
+                    None => Button::Both(press_type),
+                    Some(ButtonId::Button1) => Button::Button1Then2(press_type),
+                    Some(ButtonId::Button2) => Button::Button2Then1(press_type),
+                });
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::time::Instant;
+
+/// Source of the current time.
+///
+/// The event loop reads the time only from the clock of its
+/// [`Platform`](crate::event_loop::Platform), for the button states, the backlight, the update
+/// schedule and the frame pacing. Tests use a clock that is advanced by hand instead, e.g.
+/// `testing::ManualClock`.
+pub trait Clock {
+    /// Get the current time.
+    fn now(&self) -> Instant;
+}
+
+/// Clock that reads the time of the system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
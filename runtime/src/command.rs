@@ -0,0 +1,29 @@
+use std::thread::Thread;
+
+use crate::storage::Namespace;
+use crate::timer::{Timer, TimerId};
+
+/// Work for the event loop to carry out, returned by [`App::update`](crate::app::App::update),
+/// [`App::on_timer`](crate::app::App::on_timer) and
+/// [`App::handle_press`](crate::app::App::handle_press).
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Redraw the UI, since the app changed.
+    Redraw,
+    /// Wake a background thread that is parked, e.g. to start a scan right away.
+    Unpark(Thread),
+    /// Set the backlight brightness in percent, instead of the one from
+    /// [`App::backlight`](crate::app::App::backlight), until the next button press.
+    SetBacklight(u8),
+    /// Turn the backlight off until the next button press, which does not reach the app.
+    Sleep,
+    /// Commit the staged changes of a storage namespace.
+    Persist(Namespace),
+    /// Start a timer, which is delivered to [`App::on_timer`](crate::app::App::on_timer).
+    /// A running timer with the same id is replaced.
+    StartTimer(TimerId, Timer),
+    /// Stop a timer, if it is running.
+    CancelTimer(TimerId),
+    /// Stop the event loop.
+    Quit,
+}
@@ -0,0 +1,173 @@
+use std::time::{Duration, Instant};
+
+use crate::DISPLAY_SIZE;
+use crate::app::App;
+use crate::button::ButtonEdge;
+use crate::clock::{Clock, SystemClock};
+use crate::esp_buttons::Buttons;
+use crate::esp_storage::NvsStorage;
+use crate::event_loop::{EventLoop, Platform};
+use crate::storage::{SharedStorage, shared};
+use esp_idf_svc::eventloop::EspSystemEventLoop;
+use esp_idf_svc::hal::delay::Ets;
+use esp_idf_svc::hal::gpio::{AnyIOPin, PinDriver};
+use esp_idf_svc::hal::ledc::config::TimerConfig;
+use esp_idf_svc::hal::ledc::{LedcDriver, LedcTimerDriver, Resolution};
+use esp_idf_svc::hal::modem::Modem;
+use esp_idf_svc::hal::prelude::*;
+use esp_idf_svc::hal::spi::config::MODE_3;
+use esp_idf_svc::hal::spi::{SpiConfig, SpiDeviceDriver, SpiDriverConfig};
+use esp_idf_svc::nvs::EspDefaultNvsPartition;
+use mipidsi::Builder;
+use mipidsi::interface::SpiInterface;
+use mipidsi::models::ST7789;
+use mipidsi::options::{ColorInversion, Orientation, Rotation};
+use mousefood::embedded_graphics::draw_target::DrawTarget;
+use mousefood::embedded_graphics::prelude::*;
+use mousefood::prelude::*;
+
+/// Offset to align the display correctly.
+const DISPLAY_OFFSET: (u16, u16) = (52, 40);
+
+/// Peripherals and services that the runtime does not use itself.
+///
+/// They are handed over to the application on startup, see [`run_app_with`].
+pub struct Resources {
+    /// Wi-Fi and Bluetooth modem.
+    pub modem: Modem,
+    /// System event loop, needed by the Wi-Fi driver.
+    pub sys_loop: EspSystemEventLoop,
+    /// Default NVS partition.
+    pub nvs: EspDefaultNvsPartition,
+    /// Key-value storage in the default NVS partition.
+    pub storage: SharedStorage,
+}
+
+/// Buttons and backlight of the T-Display board.
+struct TDisplay {
+    buttons: Buttons,
+    backlight: LedcDriver<'static>,
+}
+
+impl Clock for TDisplay {
+    fn now(&self) -> Instant {
+        SystemClock.now()
+    }
+}
+
+impl Platform for TDisplay {
+    fn next_edge(&mut self, timeout: Duration) -> Option<ButtonEdge> {
+        self.buttons.next_edge(timeout)
+    }
+
+    fn set_backlight(&mut self, brightness: u8) {
+        let duty = self.backlight.get_max_duty() * u32::from(brightness.min(100)) / 100;
+        self.backlight.set_duty(duty).unwrap();
+    }
+}
+
+/// Run the application with the provided [`App`] implementation.
+///
+/// It initializes the hardware, sets up the display and buttons,
+/// and enters the main event loop.
+///
+/// Please note that this function is blocking and will not return.
+/// It is meant to be called once at the start of the program (e.g., in `main`).
+///
+/// Errors are not handled and will cause a panic if they occur.
+pub fn run_app(app: impl App) {
+    run_app_with(|_| app);
+}
+
+/// Run the application created by `init` from the remaining [`Resources`].
+///
+/// Use this instead of [`run_app`] when the application needs peripherals such as the Wi-Fi
+/// modem, since [`Peripherals::take`] can only be called once and the runtime already did.
+/// `init` is called after the display is set up.
+///
+/// Please note that this function is blocking and will not return.
+pub fn run_app_with<A: App>(init: impl FnOnce(Resources) -> A) {
+    esp_idf_svc::sys::link_patches();
+    esp_idf_svc::log::EspLogger::initialize_default();
+
+    let peripherals = Peripherals::take().unwrap();
+    let sys_loop = EspSystemEventLoop::take().unwrap();
+    let nvs = EspDefaultNvsPartition::take().unwrap();
+
+    // Turn on display backlight, with PWM so the event loop can dim it
+    let backlight_timer = LedcTimerDriver::new(
+        peripherals.ledc.timer0,
+        &TimerConfig::new()
+            .frequency(5.kHz().into())
+            .resolution(Resolution::Bits10),
+    )
+    .unwrap();
+    let mut backlight = LedcDriver::new(
+        peripherals.ledc.channel0,
+        backlight_timer,
+        peripherals.pins.gpio4,
+    )
+    .unwrap();
+    backlight.set_duty(backlight.get_max_duty()).unwrap();
+
+    // Configure SPI
+    let config = SpiConfig::new()
+        .write_only(true)
+        .baudrate(80u32.MHz().into())
+        .data_mode(MODE_3);
+    let spi_device = SpiDeviceDriver::new_single(
+        peripherals.spi2,
+        peripherals.pins.gpio18,
+        peripherals.pins.gpio19,
+        Option::<AnyIOPin>::None,
+        Some(peripherals.pins.gpio5),
+        &SpiDriverConfig::new(),
+        &config,
+    )
+    .unwrap();
+    let buffer = Box::leak(Box::new([0_u8; 4096]));
+    let spi_interface = SpiInterface::new(
+        spi_device,
+        PinDriver::output(peripherals.pins.gpio16).unwrap(),
+        buffer,
+    );
+
+    // Configure display
+    let mut delay = Ets;
+    let mut display = Builder::new(ST7789, spi_interface)
+        .invert_colors(ColorInversion::Inverted)
+        .reset_pin(PinDriver::output(peripherals.pins.gpio23).unwrap())
+        .display_offset(DISPLAY_OFFSET.0, DISPLAY_OFFSET.1)
+        .display_size(DISPLAY_SIZE.0, DISPLAY_SIZE.1)
+        .orientation(Orientation::new().rotate(Rotation::Deg90))
+        .init(&mut delay)
+        .expect("Failed to init display");
+
+    display
+        .clear(Rgb565::BLACK)
+        .expect("Failed to clear display");
+
+    // Configure buttons, whose interrupts queue up every edge
+    let buttons = Buttons::new(
+        PinDriver::input(peripherals.pins.gpio35).unwrap(),
+        PinDriver::input(peripherals.pins.gpio0).unwrap(),
+    )
+    .unwrap();
+
+    // Setup Mousefood and Ratatui
+    let backend = EmbeddedBackend::new(&mut display, Default::default());
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    // Create the application
+    let mut app = init(Resources {
+        modem: peripherals.modem,
+        sys_loop,
+        storage: shared(NvsStorage::new(nvs.clone())),
+        nvs,
+    });
+
+    // Enter main event loop
+    EventLoop::new(TDisplay { buttons, backlight })
+        .run(&mut app, &mut terminal)
+        .unwrap();
+}
@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+use crate::button::{ButtonEdge, ButtonId};
+use esp_idf_svc::hal::delay::TickType;
+use esp_idf_svc::hal::gpio::{Gpio0, Gpio35, Input, InterruptType, Pin, PinDriver};
+use esp_idf_svc::hal::task::queue::Queue;
+use esp_idf_svc::sys::{EspError, esp_timer_get_time, gpio_get_level, gpio_intr_enable};
+
+/// Number of edges that can be queued up while the event loop is busy.
+const QUEUE_SIZE: usize = 32;
+
+/// Edge as queued by the interrupt handlers, in microseconds since boot.
+#[derive(Debug, Clone, Copy)]
+struct RawEdge {
+    button: ButtonId,
+    pressed: bool,
+    micros: i64,
+}
+
+/// Buttons of the T-Display, whose interrupts queue up every edge.
+pub(crate) struct Buttons {
+    // Only kept so the interrupts stay subscribed
+    _button1: PinDriver<'static, Gpio35, Input>,
+    _button2: PinDriver<'static, Gpio0, Input>,
+    queue: &'static Queue<RawEdge>,
+    /// Time the interrupts were subscribed, to convert the edge times to [`Instant`]s
+    started: Instant,
+    /// Microseconds since boot at `started`
+    started_micros: i64,
+}
+
+impl Buttons {
+    /// Subscribe to the interrupts of both edges of the buttons.
+    pub(crate) fn new(
+        mut button1: PinDriver<'static, Gpio35, Input>,
+        mut button2: PinDriver<'static, Gpio0, Input>,
+    ) -> Result<Self, EspError> {
+        let queue: &'static Queue<RawEdge> = Box::leak(Box::new(Queue::new(QUEUE_SIZE)));
+        let (pin1, pin2) = (button1.pin(), button2.pin());
+        let started = Instant::now();
+        // SAFETY: reading the timer has no preconditions
+        let started_micros = unsafe { esp_timer_get_time() };
+
+        button1.set_interrupt_type(InterruptType::AnyEdge)?;
+        button2.set_interrupt_type(InterruptType::AnyEdge)?;
+        // SAFETY: the handlers only call functions that are safe in an interrupt and never
+        // block
+        unsafe {
+            button1.subscribe(move || queue_edge(queue, ButtonId::Button1, pin1))?;
+            button2.subscribe(move || queue_edge(queue, ButtonId::Button2, pin2))?;
+        }
+        button1.enable_interrupt()?;
+        button2.enable_interrupt()?;
+
+        Ok(Self {
+            _button1: button1,
+            _button2: button2,
+            queue,
+            started,
+            started_micros,
+        })
+    }
+
+    /// Wait up to `timeout` for the next queued edge.
+    pub(crate) fn next_edge(&mut self, timeout: Duration) -> Option<ButtonEdge> {
+        let (edge, _) = self.queue.recv_front(TickType::from(timeout).ticks())?;
+        let since_started = edge.micros.saturating_sub(self.started_micros).max(0) as u64;
+        Some(ButtonEdge {
+            button: edge.button,
+            pressed: edge.pressed,
+            at: self.started + Duration::from_micros(since_started),
+        })
+    }
+}
+
+/// Queue the edge of a button, called from its interrupt handler.
+fn queue_edge(queue: &Queue<RawEdge>, button: ButtonId, pin: i32) {
+    // SAFETY: the pin is a valid input, and these functions are safe in an interrupt
+    let edge = unsafe {
+        RawEdge {
+            button,
+            // The buttons are active low
+            pressed: gpio_get_level(pin) == 0,
+            micros: esp_timer_get_time(),
+        }
+    };
+    // The edge is dropped if the queue is full
+    let _ = queue.send_back(edge, 0);
+    // The interrupt is disabled before the handler is called, so enable it for the next edge
+    // right away instead of waiting for the event loop.
+    // SAFETY: the interrupt of the pin is set up by `Buttons::new`
+    unsafe {
+        gpio_intr_enable(pin);
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::mem;
+
+use crate::storage::{Entries, Storage, StorageError};
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use esp_idf_svc::sys::EspError;
+use serde::{Deserialize, Serialize};
+
+/// NVS keys of the two copies of the index of a namespace, which are written in turn.
+const INDEX_KEYS: [&str; 2] = ["index0", "index1"];
+
+impl From<EspError> for StorageError {
+    fn from(e: EspError) -> Self {
+        StorageError(e.to_string())
+    }
+}
+
+/// Storage in the default NVS partition, with an NVS namespace per namespace.
+///
+/// Every value is kept in its own NVS entry, a slot, and an index maps the keys to their
+/// slots, so keys are not limited to the 15 characters of NVS keys. A commit writes the
+/// changed values to unused slots first, and then the index to the copy that is not current,
+/// so a reset during a commit leaves the last committed index and its slots untouched.
+pub struct NvsStorage {
+    partition: EspDefaultNvsPartition,
+    namespaces: HashMap<String, NvsNamespace>,
+}
+
+impl NvsStorage {
+    /// Store the namespaces in `partition`.
+    pub fn new(partition: EspDefaultNvsPartition) -> Self {
+        Self {
+            partition,
+            namespaces: HashMap::new(),
+        }
+    }
+
+    /// Get a namespace, reading it the first time.
+    fn namespace(&mut self, name: &str) -> Result<&mut NvsNamespace, StorageError> {
+        if !self.namespaces.contains_key(name) {
+            let namespace = NvsNamespace::open(&self.partition, name)?;
+            self.namespaces.insert(name.to_string(), namespace);
+        }
+        Ok(self.namespaces.get_mut(name).unwrap())
+    }
+}
+
+/// Slots of the values of a namespace, by key.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Index {
+    /// Number of the commit that wrote the index, the copy with the higher one is current
+    generation: u32,
+    slots: BTreeMap<String, u16>,
+}
+
+/// A namespace read from NVS, including its staged changes.
+struct NvsNamespace {
+    nvs: EspNvs<NvsDefault>,
+    index: Index,
+    /// Which of the [`INDEX_KEYS`] has the current index
+    current: usize,
+    entries: Entries,
+    /// Keys that were changed or removed since the last commit
+    dirty: BTreeSet<String>,
+}
+
+impl NvsNamespace {
+    /// Open the NVS namespace `name`, and read its current index and values.
+    ///
+    /// A copy of the index that cannot be deserialized, e.g. after a reset while writing it,
+    /// is ignored.
+    fn open(partition: &EspDefaultNvsPartition, name: &str) -> Result<Self, StorageError> {
+        let nvs = EspNvs::new(partition.clone(), name, true)?;
+        let mut current: Option<(usize, Index)> = None;
+        for (i, key) in INDEX_KEYS.into_iter().enumerate() {
+            let Some(index) =
+                read_blob(&nvs, key)?.and_then(|data| postcard::from_bytes::<Index>(&data).ok())
+            else {
+                continue;
+            };
+            if current
+                .as_ref()
+                .is_none_or(|(_, newest)| index.generation > newest.generation)
+            {
+                current = Some((i, index));
+            }
+        }
+        let (current, index) = current.unwrap_or_default();
+
+        let mut entries = Entries::new();
+        for (key, &slot) in &index.slots {
+            if let Some(value) = read_blob(&nvs, &slot_key(slot))? {
+                entries.insert(key.clone(), value);
+            }
+        }
+        Ok(Self {
+            nvs,
+            index,
+            current,
+            entries,
+            dirty: BTreeSet::new(),
+        })
+    }
+
+    /// Write the changed values and the new index, and free the slots of the old values.
+    ///
+    /// The new index is current as soon as it is written, so removing the old slots is only
+    /// tried once, and a slot that cannot be removed is reused by a later commit.
+    fn commit(&mut self) -> Result<(), StorageError> {
+        if self.dirty.is_empty() {
+            return Ok(());
+        }
+
+        // The values go to slots that the current index does not use, so it stays valid
+        let mut used: BTreeSet<u16> = self.index.slots.values().copied().collect();
+        let mut slots = self.index.slots.clone();
+        for key in &self.dirty {
+            let Some(value) = self.entries.get(key) else {
+                slots.remove(key);
+                continue;
+            };
+            let slot = (0..=u16::MAX)
+                .find(|slot| !used.contains(slot))
+                .ok_or_else(|| StorageError("no free NVS slot".to_string()))?;
+            self.nvs.set_blob(&slot_key(slot), value)?;
+            used.insert(slot);
+            slots.insert(key.clone(), slot);
+        }
+
+        let index = Index {
+            generation: self.index.generation + 1,
+            slots,
+        };
+        let next = 1 - self.current;
+        self.nvs
+            .set_blob(INDEX_KEYS[next], &postcard::to_allocvec(&index)?)?;
+        let old = mem::replace(&mut self.index, index);
+        self.current = next;
+        self.dirty.clear();
+
+        for (key, slot) in old.slots {
+            if self.index.slots.get(&key) != Some(&slot) {
+                let _ = self.nvs.remove(&slot_key(slot));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// NVS key of a slot.
+fn slot_key(slot: u16) -> String {
+    format!("v{slot}")
+}
+
+/// Read a blob from NVS.
+fn read_blob(nvs: &EspNvs<NvsDefault>, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+    let Some(len) = nvs.blob_len(key)? else {
+        return Ok(None);
+    };
+    let mut data = vec![0; len];
+    Ok(nvs.get_blob(key, &mut data)?.map(<[u8]>::to_vec))
+}
+
+impl Storage for NvsStorage {
+    fn get_bytes(&mut self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.namespace(namespace)?.entries.get(key).cloned())
+    }
+
+    fn put_bytes(
+        &mut self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), StorageError> {
+        let namespace = self.namespace(namespace)?;
+        if namespace.entries.get(key) != Some(&value) {
+            namespace.entries.insert(key.to_string(), value);
+            namespace.dirty.insert(key.to_string());
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, namespace: &str, key: &str) -> Result<(), StorageError> {
+        let namespace = self.namespace(namespace)?;
+        if namespace.entries.remove(key).is_some() {
+            namespace.dirty.insert(key.to_string());
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, namespace: &str) -> Result<(), StorageError> {
+        match self.namespaces.get_mut(namespace) {
+            Some(namespace) => namespace.commit(),
+            None => Ok(()),
+        }
+    }
+}
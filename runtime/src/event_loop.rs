@@ -0,0 +1,424 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::app::{App, Context};
+use crate::backlight::IdleBacklight;
+use crate::button::{ButtonEdge, ButtonEvent, ButtonId, ButtonPressType, ButtonState, ChordState};
+use crate::clock::Clock;
+use crate::command::Command;
+use crate::frame::FrameStats;
+use crate::timer::Timers;
+use mousefood::prelude::*;
+use mousefood::ratatui::backend::Backend;
+
+/// Hardware the event loop runs on, and the clock it reads the time from.
+///
+/// Implemented for the ESP32 T-Display (behind the `esp` feature) and by the simulator.
+pub trait Platform: Clock {
+    /// Block the current task until a button goes down or up, or until `timeout` passed.
+    ///
+    /// Edges are returned in the order they happened, with the time they happened at, even if
+    /// they happened while the event loop was busy, e.g. drawing.
+    fn next_edge(&mut self, timeout: Duration) -> Option<ButtonEdge>;
+
+    /// Set the backlight brightness in percent, where 0 turns it off.
+    fn set_backlight(&mut self, brightness: u8);
+}
+
+/// Main event loop, shared by every [`Platform`].
+///
+/// It updates the [`App`], dims the backlight while the buttons are idle, redraws the UI and
+/// then waits for the buttons, forwarding the detected presses to the app, and carries out the
+/// [`Command`]s the app returns. The UI is only redrawn when the app changed, at most
+/// [`FrameConfig::max_fps`](crate::frame::FrameConfig::max_fps) times per second, and the loop
+/// sleeps while it waits instead of spinning.
+pub struct EventLoop<P> {
+    platform: P,
+    button1_state: ButtonState,
+    button2_state: ButtonState,
+    chord: ChordState,
+    backlight: IdleBacklight,
+    /// Brightness last set on the platform
+    brightness: Option<u8>,
+    /// Brightness set by [`Command::SetBacklight`], until the next button press
+    backlight_override: Option<u8>,
+    /// Whether the buttons that turned the backlight back on are still held
+    waking: bool,
+    /// Whether the app changed since it was last drawn
+    dirty: bool,
+    /// Start of the last frame
+    last_frame: Option<Instant>,
+    /// Time of the last update of the app
+    last_update: Option<Instant>,
+    /// Timers started by the app
+    timers: Timers,
+    /// Whether the app returned [`Command::Quit`]
+    quit: bool,
+    /// Measured times of the frames drawn so far
+    stats: FrameStats,
+}
+
+impl<P: Platform> EventLoop<P> {
+    /// Create a new event loop running on the given platform.
+    pub fn new(platform: P) -> Self {
+        Self {
+            backlight: IdleBacklight::new(platform.now()),
+            platform,
+            button1_state: ButtonState::default(),
+            button2_state: ButtonState::default(),
+            chord: ChordState::default(),
+            brightness: None,
+            backlight_override: None,
+            waking: false,
+            dirty: true,
+            last_frame: None,
+            last_update: None,
+            timers: Timers::default(),
+            quit: false,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Get a mutable reference to the underlying platform.
+    pub fn platform_mut(&mut self) -> &mut P {
+        &mut self.platform
+    }
+
+    /// Get the measured times of the frames drawn so far.
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Check if the app changed since it was last drawn.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Check if the app returned [`Command::Quit`].
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Carry out the commands returned by the app.
+    ///
+    /// A [`Command::Persist`] that fails is handed back to [`App::on_persist_error`], and the
+    /// commands it returns are carried out before the following ones.
+    pub fn execute(&mut self, app: &mut impl App, commands: Vec<Command>) {
+        for command in commands {
+            match command {
+                Command::Redraw => self.dirty = true,
+                Command::Unpark(thread) => thread.unpark(),
+                Command::SetBacklight(brightness) => {
+                    self.backlight_override = Some(brightness.min(100));
+                }
+                Command::Sleep => {
+                    self.backlight_override = None;
+                    self.backlight.sleep();
+                    // The buttons that are still held, e.g. of the chord that returned it, do
+                    // not wake it again
+                    self.waking =
+                        self.button1_state.is_pressed() || self.button2_state.is_pressed();
+                }
+                Command::Persist(namespace) => {
+                    if let Err(e) = namespace.commit() {
+                        let commands = app.on_persist_error(&namespace, e);
+                        self.execute(app, commands);
+                    }
+                }
+                Command::StartTimer(id, timer) => {
+                    self.timers.start(id, timer, self.platform.now());
+                }
+                Command::CancelTimer(id) => self.timers.cancel(id),
+                Command::Quit => self.quit = true,
+            }
+        }
+    }
+
+    /// Wait up to `timeout` for the buttons to change, and forward any detected press to the
+    /// application.
+    ///
+    /// All edges that are queued up are handled at once, and the app gets a
+    /// [`ButtonEvent::HoldProgress`] for every button that is still held afterwards. A press that
+    /// turns the backlight back on is not forwarded.
+    pub fn handle_buttons(&mut self, app: &mut impl App, timeout: Duration) {
+        let mut edge = self.platform.next_edge(timeout);
+        while let Some(next) = edge {
+            self.handle_edge(app, next);
+            edge = self.platform.next_edge(Duration::ZERO);
+        }
+
+        // Apply the edges that were held back as possible bounces
+        let now = self.platform.now();
+        for button in [ButtonId::Button1, ButtonId::Button2] {
+            self.settle(app, button, now);
+        }
+
+        for button in [ButtonId::Button1, ButtonId::Button2] {
+            if let Some(pressed_at) = self.state_mut(button).pressed_at() {
+                // Keep the backlight on while a button is held, unless it was put to sleep
+                if !self.backlight.is_asleep() {
+                    self.backlight.wake(now);
+                }
+                let held = now.saturating_duration_since(pressed_at);
+                self.send_event(app, ButtonEvent::HoldProgress(button, held));
+            }
+        }
+    }
+
+    /// Update the state of a button with an edge, and forward the detected presses.
+    fn handle_edge(&mut self, app: &mut impl App, edge: ButtonEdge) {
+        // Apply an edge that was held back and the presses that were due before this edge
+        // first, so all changes reach the app in order, e.g. before a press that wakes the
+        // backlight
+        for button in [ButtonId::Button1, ButtonId::Button2] {
+            self.settle(app, button, edge.at);
+        }
+
+        if edge.pressed {
+            // A button that is still held kept the backlight on until now, as in
+            // `handle_buttons`, even if its edges were queued up
+            let held = self.button1_state.is_pressed() || self.button2_state.is_pressed();
+            if held && !self.backlight.is_asleep() {
+                self.backlight.wake(edge.at);
+            }
+            let off = match self.backlight_override {
+                Some(brightness) => brightness == 0,
+                None => self.backlight.is_off(&app.backlight(), edge.at),
+            };
+            if off {
+                self.waking = true;
+            }
+            self.backlight.wake(edge.at);
+            self.backlight_override = None;
+        }
+
+        let config = app.button_config(edge.button);
+        let was_pressed = self.state_mut(edge.button).is_pressed();
+        let mut presses = Vec::new();
+        self.state_mut(edge.button)
+            .update(&config, edge.pressed, edge.at, |press_type| {
+                presses.push(press_type);
+            });
+        self.send_change(app, edge.button, was_pressed);
+        self.forward(app, edge.button, presses);
+        self.update_chord(app);
+    }
+
+    /// Apply the edge of a button that was held back until `now`, and forward the presses that
+    /// are due.
+    fn settle(&mut self, app: &mut impl App, button: ButtonId, now: Instant) {
+        let config = app.button_config(button);
+        let was_pressed = self.state_mut(button).is_pressed();
+        let mut presses = Vec::new();
+        self.state_mut(button).settle(&config, now, |press_type| {
+            presses.push(press_type);
+        });
+        self.send_change(app, button, was_pressed);
+        self.forward(app, button, presses);
+        self.update_chord(app);
+    }
+
+    /// Send a [`ButtonEvent::Down`] or [`ButtonEvent::Up`] to the app if the button changed.
+    fn send_change(&mut self, app: &mut impl App, button: ButtonId, was_pressed: bool) {
+        match (was_pressed, self.state_mut(button).is_pressed()) {
+            (false, true) => self.send_event(app, ButtonEvent::Down(button)),
+            (true, false) => self.send_event(app, ButtonEvent::Up(button)),
+            _ => {}
+        }
+    }
+
+    /// Send a button event to the app, unless the button turned the backlight back on.
+    fn send_event(&mut self, app: &mut impl App, event: ButtonEvent) {
+        if !self.waking {
+            let commands = app.handle_event(event);
+            self.execute(app, commands);
+        }
+    }
+
+    /// Start or end a chord of both buttons, and forward it to the app once it ended.
+    ///
+    /// The press type of the chord uses the config of button 1.
+    fn update_chord(&mut self, app: &mut impl App) {
+        let config = app.button_config(ButtonId::Button1);
+        let mut presses = Vec::new();
+        self.chord.update(
+            &config,
+            &mut self.button1_state,
+            &mut self.button2_state,
+            |button| presses.push(button),
+        );
+        for button in presses {
+            if !self.waking {
+                let commands = app.handle_press(button);
+                self.execute(app, commands);
+            }
+        }
+    }
+
+    /// Forward the detected presses to the app, unless they turned the backlight back on.
+    fn forward(&mut self, app: &mut impl App, button: ButtonId, presses: Vec<ButtonPressType>) {
+        if self.waking {
+            self.waking = self.button1_state.is_pressed() || self.button2_state.is_pressed();
+            if !self.waking {
+                // A short press would only be reported after the double-press window
+                self.button1_state.discard_first_press();
+                self.button2_state.discard_first_press();
+            }
+        } else {
+            for press_type in presses {
+                let commands = app.handle_press(button.press(press_type));
+                self.execute(app, commands);
+            }
+        }
+    }
+
+    fn state_mut(&mut self, button: ButtonId) -> &mut ButtonState {
+        match button {
+            ButtonId::Button1 => &mut self.button1_state,
+            ButtonId::Button2 => &mut self.button2_state,
+        }
+    }
+
+    /// Update the app if the last update is at least a tick interval ago, and carry out the
+    /// commands it returns.
+    pub fn update(&mut self, app: &mut impl App) {
+        let now = self.platform.now();
+        let tick_interval = app.frame_config().tick_interval;
+        let dt = self.last_update.map_or(Duration::ZERO, |last_update| {
+            now.duration_since(last_update)
+        });
+        if self.last_update.is_some() && dt < tick_interval {
+            return;
+        }
+
+        let ctx = Context {
+            now,
+            brightness: self.brightness.unwrap_or_default(),
+            frame_stats: self.stats,
+        };
+        let commands = app.update(&ctx, dt);
+        self.last_update = Some(now);
+        self.execute(app, commands);
+    }
+
+    /// Deliver the timers that are due to the app, and carry out the returned commands.
+    pub fn fire_timers(&mut self, app: &mut impl App) {
+        for id in self.timers.expire(self.platform.now()) {
+            let commands = app.on_timer(id);
+            self.execute(app, commands);
+        }
+    }
+
+    /// Get the timers started by the app.
+    pub fn timers(&self) -> &Timers {
+        &self.timers
+    }
+
+    /// Set the backlight brightness for the time since the last button press.
+    ///
+    /// A new brightness marks the app as changed, since the simulator dims the UI when drawing.
+    pub fn update_backlight(&mut self, app: &impl App) {
+        let brightness = self.backlight_override.unwrap_or_else(|| {
+            self.backlight
+                .brightness(&app.backlight(), self.platform.now())
+        });
+        if self.brightness != Some(brightness) {
+            self.platform.set_backlight(brightness);
+            self.brightness = Some(brightness);
+            self.dirty = true;
+        }
+    }
+
+    /// Draw the app if it changed and the last frame is at least a frame interval ago.
+    ///
+    /// Returns whether a frame was drawn, and records its times in the [`FrameStats`].
+    pub fn draw<B: Backend>(
+        &mut self,
+        app: &impl App,
+        terminal: &mut Terminal<B>,
+    ) -> io::Result<bool> {
+        let start = self.platform.now();
+        let frame_interval = app.frame_config().frame_interval();
+        let too_early = self
+            .last_frame
+            .is_some_and(|last_frame| start.duration_since(last_frame) < frame_interval);
+        if !self.dirty || too_early {
+            return Ok(false);
+        }
+
+        let mut rendered = start;
+        terminal.draw(|f| {
+            app.draw(f);
+            rendered = self.platform.now();
+        })?;
+        let end = self.platform.now();
+        self.stats.record(end - start, end - rendered);
+        self.last_frame = Some(start);
+        self.dirty = false;
+        Ok(true)
+    }
+
+    /// Time to wait for the buttons before the next iteration.
+    ///
+    /// This is the time until the next update, or less if the app changed and its next frame is
+    /// due earlier, if a button has a press that is due earlier, e.g. a repeat, or if a timer is
+    /// due earlier.
+    fn timeout(&self, app: &impl App) -> Duration {
+        let config = app.frame_config();
+        let next_frame = self
+            .last_frame
+            .filter(|_| self.dirty)
+            .map(|last_frame| last_frame + config.frame_interval());
+        let button1 = self
+            .button1_state
+            .deadline(&app.button_config(ButtonId::Button1));
+        let button2 = self
+            .button2_state
+            .deadline(&app.button_config(ButtonId::Button2));
+        let next_update = self
+            .last_update
+            .map(|last_update| last_update + config.tick_interval);
+        let next_timer = self.timers.deadline();
+        let now = self.platform.now();
+        [next_update, next_frame, button1, button2, next_timer]
+            .into_iter()
+            .flatten()
+            .map(|deadline| deadline.saturating_duration_since(now))
+            .fold(config.tick_interval, Duration::min)
+    }
+
+    /// Run a single iteration of the event loop, which ends by waiting for the buttons.
+    pub fn run_once<B: Backend>(
+        &mut self,
+        app: &mut impl App,
+        terminal: &mut Terminal<B>,
+    ) -> io::Result<()> {
+        // Let the app do its own work
+        self.update(app);
+        self.fire_timers(app);
+
+        // Dim the backlight while the buttons are idle
+        self.update_backlight(app);
+
+        // Draw the UI if it changed
+        self.draw(app, terminal)?;
+
+        // Sleep until a button changes, or the next update or timer is due
+        let timeout = self.timeout(app);
+        self.handle_buttons(app, timeout);
+        Ok(())
+    }
+
+    /// Run the event loop until the app returns [`Command::Quit`], or drawing to the terminal
+    /// fails.
+    pub fn run<B: Backend>(
+        mut self,
+        app: &mut impl App,
+        terminal: &mut Terminal<B>,
+    ) -> io::Result<()> {
+        while !self.quit {
+            self.run_once(app, terminal)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use core::fmt;
+use std::time::Duration;
+
+/// How often the event loop redraws and updates an app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameConfig {
+    /// Maximum number of frames per second, even if the app changes more often.
+    pub max_fps: u32,
+    /// Time between two updates of the app, see [`App::update`](crate::app::App::update).
+    pub tick_interval: Duration,
+}
+
+impl Default for FrameConfig {
+    fn default() -> Self {
+        Self {
+            max_fps: 30,
+            tick_interval: Duration::from_millis(50),
+        }
+    }
+}
+
+impl FrameConfig {
+    /// Shortest time between two frames, or zero if the frame rate is not limited.
+    pub fn frame_interval(&self) -> Duration {
+        match self.max_fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        }
+    }
+}
+
+/// Measured times of the frames drawn by the event loop.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Number of frames drawn.
+    pub frames: u64,
+    /// Time to render and flush the last frame.
+    pub last_frame: Duration,
+    /// Time to write the last frame to the display after rendering it.
+    pub last_flush: Duration,
+    /// Moving average of the frame time.
+    pub average_frame: Duration,
+    /// Moving average of the flush time.
+    pub average_flush: Duration,
+    /// Longest frame time.
+    pub max_frame: Duration,
+}
+
+impl FrameStats {
+    /// Record a frame that took `frame` in total, of which `flush` was spent writing it to the
+    /// display.
+    pub fn record(&mut self, frame: Duration, flush: Duration) {
+        if self.frames == 0 {
+            self.average_frame = frame;
+            self.average_flush = flush;
+        } else {
+            self.average_frame = (self.average_frame * 7 + frame) / 8;
+            self.average_flush = (self.average_flush * 7 + flush) / 8;
+        }
+        self.frames += 1;
+        self.last_frame = frame;
+        self.last_flush = flush;
+        self.max_frame = self.max_frame.max(frame);
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |duration: Duration| duration.as_secs_f32() * 1000.0;
+        write!(
+            f,
+            "{} frames, frame {:.1}ms (avg {:.1}ms, max {:.1}ms), flush {:.1}ms (avg {:.1}ms)",
+            self.frames,
+            ms(self.last_frame),
+            ms(self.average_frame),
+            ms(self.max_frame),
+            ms(self.last_flush),
+            ms(self.average_flush)
+        )
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use crate::button::{ButtonConfig, ButtonEvent, ButtonId};
+use mousefood::ratatui::buffer::Buffer;
+use mousefood::ratatui::layout::Rect;
+use mousefood::ratatui::style::{Color, Style};
+use mousefood::ratatui::symbols::line;
+use mousefood::ratatui::widgets::Widget;
+
+/// Bar that fills while a button is held, and changes its color once the press is long.
+///
+/// It is fed with the [`ButtonEvent`]s of the app, and rendered as a single line, e.g. over
+/// the bottom border of a footer:
+///
+/// ```ignore
+/// fn handle_event(&mut self, event: ButtonEvent) -> Vec<Command> {
+///     let config = self.button_config(event.button());
+///     if self.hold_indicator.handle_event(event, &config) {
+///         vec![Command::Redraw]
+///     } else {
+///         Vec::new()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoldIndicator {
+    /// Button that is held, and for how long so far
+    held: Option<(ButtonId, Duration)>,
+    /// Long-press threshold of the held button, at which the bar is full
+    long_press: Duration,
+    /// Style of the bar before the long-press threshold
+    style: Style,
+    /// Style of the bar from the long-press threshold on
+    long_press_style: Style,
+}
+
+impl Default for HoldIndicator {
+    fn default() -> Self {
+        Self {
+            held: None,
+            long_press: ButtonConfig::default().long_press,
+            style: Style::new().fg(Color::Cyan),
+            long_press_style: Style::new().fg(Color::Green),
+        }
+    }
+}
+
+impl HoldIndicator {
+    /// Set the style of the bar before the long-press threshold.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the style of the bar from the long-press threshold on.
+    pub fn long_press_style(mut self, style: Style) -> Self {
+        self.long_press_style = style;
+        self
+    }
+
+    /// Follow a button event, with the config of its button.
+    ///
+    /// Only the button that went down first is shown. Returns whether the bar changed.
+    pub fn handle_event(&mut self, event: ButtonEvent, config: &ButtonConfig) -> bool {
+        let before = *self;
+        match (event, self.held) {
+            (ButtonEvent::Down(button), None) => {
+                self.held = Some((button, Duration::ZERO));
+                self.long_press = config.long_press;
+            }
+            (ButtonEvent::HoldProgress(button, held), Some((held_button, _)))
+                if button == held_button =>
+            {
+                self.held = Some((button, held));
+            }
+            (ButtonEvent::Up(button), Some((held_button, _))) if button == held_button => {
+                self.held = None;
+            }
+            _ => {}
+        }
+        *self != before
+    }
+
+    /// Get the button that is held, and for how long so far.
+    pub fn held(&self) -> Option<(ButtonId, Duration)> {
+        self.held
+    }
+
+    /// Check if the held button reached the long-press threshold.
+    pub fn is_long_press(&self) -> bool {
+        self.held.is_some_and(|(_, held)| held >= self.long_press)
+    }
+}
+
+impl Widget for &HoldIndicator {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let Some((_, held)) = self.held.filter(|_| !area.is_empty()) else {
+            return;
+        };
+        let (width, style) = if self.is_long_press() {
+            (area.width, self.long_press_style)
+        } else {
+            let filled = held.as_nanos() * u128::from(area.width) / self.long_press.as_nanos();
+            (filled as u16, self.style)
+        };
+        for x in area.left()..area.left() + width {
+            buf[(x, area.top())]
+                .set_symbol(line::THICK_HORIZONTAL)
+                .set_style(style);
+        }
+    }
+}
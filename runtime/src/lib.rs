@@ -0,0 +1,50 @@
+/// Display size in pixels, before rotation.
+///
+/// The T-Display is mounted in landscape (rotated by 90°), so apps are rendered into 240x135.
+pub const DISPLAY_SIZE: (u16, u16) = (135, 240);
+
+/// Application trait.
+pub mod app;
+
+/// Backlight brightness and idle dimming.
+pub mod backlight;
+
+/// Button handling.
+pub mod button;
+
+/// Source of the current time.
+pub mod clock;
+
+/// Commands that apps return for the event loop to carry out.
+pub mod command;
+
+/// Platform-agnostic main event loop.
+pub mod event_loop;
+
+/// Frame pacing and timing.
+pub mod frame;
+
+/// Feedback while a button is held.
+pub mod hold_indicator;
+
+/// Persistent key-value storage.
+pub mod storage;
+
+/// One-shot and repeating timers of an app.
+pub mod timer;
+
+/// ESP32 T-Display hardware setup.
+#[cfg(feature = "esp")]
+pub mod esp;
+
+/// Interrupt-driven buttons of the ESP32 T-Display.
+#[cfg(feature = "esp")]
+mod esp_buttons;
+
+/// Storage in the ESP32 NVS partition.
+#[cfg(feature = "esp")]
+pub mod esp_storage;
+
+/// Headless rendering and snapshot testing.
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,270 @@
+use core::fmt;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Serialized values of a namespace, by key.
+pub(crate) type Entries = BTreeMap<String, Vec<u8>>;
+
+/// Error returned by a [`Storage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageError(pub String);
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "storage failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError(e.to_string())
+    }
+}
+
+impl From<postcard::Error> for StorageError {
+    fn from(e: postcard::Error) -> Self {
+        StorageError(e.to_string())
+    }
+}
+
+/// Persistent key-value storage, divided into namespaces.
+///
+/// Values are serialized with [postcard](https://docs.rs/postcard). Changes are staged until
+/// their namespace is committed, which saves all of its entries at once: after a reset during
+/// a commit, the namespace has either all or none of the changes.
+pub trait Storage {
+    /// Read the serialized value of a key, including staged changes.
+    fn get_bytes(&mut self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Stage a serialized value for a key.
+    fn put_bytes(&mut self, namespace: &str, key: &str, value: Vec<u8>)
+    -> Result<(), StorageError>;
+
+    /// Stage the removal of a key.
+    fn remove(&mut self, namespace: &str, key: &str) -> Result<(), StorageError>;
+
+    /// Save the staged changes of a namespace.
+    ///
+    /// Every commit writes to the flash on the device, so batch changes that happen often
+    /// instead of committing each of them. [`FileStorage`] rewrites the whole namespace, while
+    /// `NvsStorage` writes only the changed values and a small index of the keys.
+    fn commit(&mut self, namespace: &str) -> Result<(), StorageError>;
+
+    /// Read the value of a key, including staged changes.
+    fn get<T: DeserializeOwned>(
+        &mut self,
+        namespace: &str,
+        key: &str,
+    ) -> Result<Option<T>, StorageError>
+    where
+        Self: Sized,
+    {
+        get(self, namespace, key)
+    }
+
+    /// Stage a value for a key.
+    fn put<T: Serialize + ?Sized>(
+        &mut self,
+        namespace: &str,
+        key: &str,
+        value: &T,
+    ) -> Result<(), StorageError>
+    where
+        Self: Sized,
+    {
+        put(self, namespace, key, value)
+    }
+}
+
+/// Read and deserialize the value of a key.
+fn get<T: DeserializeOwned>(
+    storage: &mut (impl Storage + ?Sized),
+    namespace: &str,
+    key: &str,
+) -> Result<Option<T>, StorageError> {
+    match storage.get_bytes(namespace, key)? {
+        Some(bytes) => Ok(Some(postcard::from_bytes(&bytes)?)),
+        None => Ok(None),
+    }
+}
+
+/// Serialize and stage the value of a key.
+fn put<T: Serialize + ?Sized>(
+    storage: &mut (impl Storage + ?Sized),
+    namespace: &str,
+    key: &str,
+    value: &T,
+) -> Result<(), StorageError> {
+    storage.put_bytes(namespace, key, postcard::to_allocvec(value)?)
+}
+
+/// Storage shared between threads, e.g. the UI and a background task.
+pub type SharedStorage = Arc<Mutex<dyn Storage + Send>>;
+
+/// Share a storage between threads.
+pub fn shared(storage: impl Storage + Send + 'static) -> SharedStorage {
+    Arc::new(Mutex::new(storage))
+}
+
+/// Handle to a single namespace of a [`SharedStorage`].
+///
+/// It is cheap to clone, so every part of an app can keep its own handle.
+#[derive(Clone)]
+pub struct Namespace {
+    storage: SharedStorage,
+    name: String,
+}
+
+impl Namespace {
+    /// Get a handle to the namespace `name` of `storage`.
+    ///
+    /// On the ESP32, namespace names have at most 15 characters.
+    pub fn new(storage: &SharedStorage, name: impl Into<String>) -> Self {
+        Self {
+            storage: Arc::clone(storage),
+            name: name.into(),
+        }
+    }
+
+    /// Name of the namespace.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Read the value of a key, including staged changes.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, StorageError> {
+        get(&mut *self.storage.lock().unwrap(), &self.name, key)
+    }
+
+    /// Stage a value for a key.
+    pub fn put<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result<(), StorageError> {
+        put(&mut *self.storage.lock().unwrap(), &self.name, key, value)
+    }
+
+    /// Stage the removal of a key.
+    pub fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.storage.lock().unwrap().remove(&self.name, key)
+    }
+
+    /// Save the staged changes of the namespace.
+    pub fn commit(&self) -> Result<(), StorageError> {
+        self.storage.lock().unwrap().commit(&self.name)
+    }
+}
+
+impl fmt::Debug for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Namespace")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// Namespaces read from a storage, including their staged changes.
+#[derive(Debug, Default)]
+pub(crate) struct Cache {
+    namespaces: HashMap<String, Entries>,
+}
+
+impl Cache {
+    /// Get the entries of a namespace, reading them with `load` the first time.
+    ///
+    /// Entries that cannot be deserialized, e.g. after a reset during the first save, are
+    /// discarded.
+    pub(crate) fn entries(
+        &mut self,
+        namespace: &str,
+        load: impl FnOnce() -> Result<Option<Vec<u8>>, StorageError>,
+    ) -> Result<&mut Entries, StorageError> {
+        if !self.namespaces.contains_key(namespace) {
+            let entries = load()?
+                .and_then(|data| postcard::from_bytes(&data).ok())
+                .unwrap_or_default();
+            self.namespaces.insert(namespace.to_string(), entries);
+        }
+        Ok(self.namespaces.get_mut(namespace).unwrap())
+    }
+
+    /// Serialize the entries of a namespace, or `None` if they were never read.
+    pub(crate) fn serialize(&self, namespace: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match self.namespaces.get(namespace) {
+            Some(entries) => Ok(Some(postcard::to_allocvec(entries)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Storage with a file per namespace, for the simulator and host tests.
+///
+/// A commit writes the namespace to a temporary file first and then renames it.
+#[derive(Debug)]
+pub struct FileStorage {
+    dir: PathBuf,
+    cache: Cache,
+}
+
+impl FileStorage {
+    /// Store the namespaces in the directory at `dir`, which is created on the first commit.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            cache: Cache::default(),
+        }
+    }
+
+    /// Path of the file of a namespace.
+    fn path(&self, namespace: &str) -> PathBuf {
+        self.dir.join(format!("{namespace}.bin"))
+    }
+
+    /// Get the entries of a namespace, reading its file the first time.
+    fn entries(&mut self, namespace: &str) -> Result<&mut Entries, StorageError> {
+        let path = self.path(namespace);
+        self.cache.entries(namespace, || match fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        })
+    }
+}
+
+impl Storage for FileStorage {
+    fn get_bytes(&mut self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.entries(namespace)?.get(key).cloned())
+    }
+
+    fn put_bytes(
+        &mut self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), StorageError> {
+        self.entries(namespace)?.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn remove(&mut self, namespace: &str, key: &str) -> Result<(), StorageError> {
+        self.entries(namespace)?.remove(key);
+        Ok(())
+    }
+
+    fn commit(&mut self, namespace: &str) -> Result<(), StorageError> {
+        let Some(data) = self.cache.serialize(namespace)? else {
+            return Ok(());
+        };
+        let path = self.path(namespace);
+        let temp_path = path.with_extension("tmp");
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&temp_path, data)?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+}
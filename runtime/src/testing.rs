@@ -0,0 +1,386 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::DISPLAY_SIZE;
+use crate::app::App;
+use crate::button::{Button, ButtonEdge, ButtonEvent, ButtonId};
+use crate::clock::Clock;
+use crate::command::Command;
+use crate::event_loop::Platform;
+use mousefood::embedded_graphics::pixelcolor::{Rgb565, Rgb888};
+use mousefood::embedded_graphics::prelude::{
+    DrawTarget, OriginDimensions, Pixel, Point, RgbColor, Size,
+};
+use mousefood::prelude::{EmbeddedBackend, Terminal};
+use mousefood::ratatui::backend::TestBackend;
+use mousefood::ratatui::buffer::Buffer;
+pub use tempfile::TempDir;
+
+/// Terminal size in cells of the T-Display with mousefood's default 6x10 font.
+pub const TERMINAL_SIZE: (u16, u16) = (DISPLAY_SIZE.1 / 6, DISPLAY_SIZE.0 / 10);
+
+/// Environment variable that makes snapshot assertions overwrite the stored snapshots.
+pub const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// In-memory [`DrawTarget`] with the same size and color format as the T-Display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    size: Size,
+    pixels: Vec<Rgb565>,
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        // The display is rotated by 90°, so width and height are swapped
+        Self::new(Size::new(DISPLAY_SIZE.1.into(), DISPLAY_SIZE.0.into()))
+    }
+}
+
+impl Framebuffer {
+    /// Create a black framebuffer of the given size.
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![Rgb565::BLACK; (size.width * size.height) as usize],
+        }
+    }
+
+    /// Get the color of the pixel at the given position.
+    pub fn pixel(&self, point: Point) -> Option<Rgb565> {
+        let index = self.index(point)?;
+        self.pixels.get(index).copied()
+    }
+
+    /// Convert the framebuffer to 8-bit RGB bytes, row by row.
+    pub fn to_rgb888(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&color| {
+                let color = Rgb888::from(color);
+                [color.r(), color.g(), color.b()]
+            })
+            .collect()
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (u32::try_from(point.x).ok()?, u32::try_from(point.y).ok()?);
+        (x < self.size.width && y < self.size.height).then(|| (y * self.size.width + x) as usize)
+    }
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(index) = self.index(point) {
+                self.pixels[index] = color;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`Clock`] that only moves when it is advanced, shared by all of its clones.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl ManualClock {
+    /// Create a clock that stands at `now`.
+    pub fn new(now: Instant) -> Self {
+        Self {
+            now: Rc::new(Cell::new(now)),
+        }
+    }
+
+    /// Move the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Move the clock forward to `at`, unless it is already later.
+    pub fn advance_to(&self, at: Instant) {
+        self.now.set(self.now.get().max(at));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+/// [`Platform`] without hardware, whose button edges are queued up by the test.
+///
+/// [`Platform::next_edge`] returns the queued edges right away, or `None` without waiting.
+/// The time only moves when the test advances the [`ManualClock`].
+#[derive(Debug, Default)]
+pub struct TestPlatform {
+    /// Clock of the platform.
+    pub clock: ManualClock,
+    /// Edges returned by [`Platform::next_edge`], oldest first.
+    pub edges: VecDeque<ButtonEdge>,
+    /// Timeouts that [`Platform::next_edge`] was called with.
+    pub timeouts: Vec<Duration>,
+    /// Brightness values the backlight was set to.
+    pub backlight: Vec<u8>,
+}
+
+impl TestPlatform {
+    /// Queue an edge of `button` at `at`.
+    pub fn push_edge(&mut self, button: ButtonId, pressed: bool, at: Instant) -> &mut Self {
+        self.edges.push_back(ButtonEdge {
+            button,
+            pressed,
+            at,
+        });
+        self
+    }
+
+    /// Queue a press of `button` that starts at `at` and is held for `duration`.
+    pub fn click(&mut self, button: ButtonId, at: Instant, duration: Duration) -> &mut Self {
+        self.push_edge(button, true, at)
+            .push_edge(button, false, at + duration)
+    }
+}
+
+impl Clock for TestPlatform {
+    fn now(&self) -> Instant {
+        self.clock.now()
+    }
+}
+
+impl Platform for TestPlatform {
+    fn next_edge(&mut self, timeout: Duration) -> Option<ButtonEdge> {
+        self.timeouts.push(timeout);
+        self.edges.pop_front()
+    }
+
+    fn set_backlight(&mut self, brightness: u8) {
+        self.backlight.push(brightness);
+    }
+}
+
+/// Headless harness that drives an [`App`] without any hardware.
+///
+/// It renders the app into a ratatui [`Buffer`] or into a [`Framebuffer`] and compares the
+/// result against golden snapshots stored in the `tests/snapshots` folder of the crate under
+/// test. Snapshots are only written when the [`UPDATE_SNAPSHOTS`] environment variable is set,
+/// which records missing ones and rewrites all others. Without it, a missing snapshot fails
+/// the test.
+///
+/// ```ignore
+/// let mut harness = Harness::new(AppState::new());
+/// harness.press(Button::Button1(ButtonPressType::Short));
+/// harness.assert_snapshot("stats_tab");
+/// ```
+pub struct Harness<A> {
+    app: A,
+    terminal: Terminal<TestBackend>,
+    /// Commands returned by the application, not taken yet
+    commands: Vec<Command>,
+}
+
+impl<A: App> Harness<A> {
+    /// Create a harness with the size of the T-Display.
+    pub fn new(app: A) -> Self {
+        Self::with_size(app, TERMINAL_SIZE.0, TERMINAL_SIZE.1)
+    }
+
+    /// Create a harness with a custom terminal size in cells.
+    pub fn with_size(app: A, width: u16, height: u16) -> Self {
+        let terminal =
+            Terminal::new(TestBackend::new(width, height)).expect("Failed to create test terminal");
+        Self {
+            app,
+            terminal,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Get a reference to the application.
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    /// Get a mutable reference to the application.
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    /// Send a button press to the application, like the event loop does.
+    ///
+    /// The returned commands are kept until [`Harness::take_commands`] is called.
+    pub fn press(&mut self, button: Button) -> &mut Self {
+        let commands = self.app.handle_press(button);
+        self.commands.extend(commands);
+        self
+    }
+
+    /// Take the commands returned by the application so far.
+    pub fn take_commands(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Send a button event to the application, like the event loop does.
+    ///
+    /// The returned commands are kept until [`Harness::take_commands`] is called.
+    pub fn event(&mut self, event: ButtonEvent) -> &mut Self {
+        let commands = self.app.handle_event(event);
+        self.commands.extend(commands);
+        self
+    }
+
+    /// Draw the application and return the rendered buffer.
+    pub fn buffer(&mut self) -> &Buffer {
+        let app = &self.app;
+        self.terminal
+            .draw(|f| app.draw(f))
+            .expect("Failed to draw to test terminal");
+        self.terminal.backend().buffer()
+    }
+
+    /// Draw the application and return the rendered text, one line per row.
+    pub fn text(&mut self) -> String {
+        buffer_to_text(self.buffer())
+    }
+
+    /// Draw the application into a [`Framebuffer`] with the mousefood backend.
+    pub fn pixels(&self) -> Framebuffer {
+        let mut framebuffer = Framebuffer::default();
+        let backend = EmbeddedBackend::new(&mut framebuffer, Default::default());
+        let mut terminal = Terminal::new(backend).expect("Failed to create pixel terminal");
+        terminal
+            .draw(|f| self.app.draw(f))
+            .expect("Failed to draw to framebuffer");
+        drop(terminal);
+        framebuffer
+    }
+
+    /// Compare the rendered text against the `<name>.txt` snapshot.
+    #[track_caller]
+    pub fn assert_snapshot(&mut self, name: &str) {
+        let actual = self.text();
+        let path = snapshot_path(name, "txt");
+        if should_record(&path) {
+            write_snapshot(&path, actual.as_bytes());
+            return;
+        }
+
+        let expected = fs::read_to_string(&path).expect("Failed to read snapshot");
+        assert!(
+            expected == actual,
+            "snapshot `{name}` does not match\n--- expected\n{expected}--- actual\n{actual}"
+        );
+    }
+
+    /// Compare the rendered pixels against the `<name>.png` snapshot.
+    #[track_caller]
+    pub fn assert_png_snapshot(&self, name: &str) {
+        let framebuffer = self.pixels();
+        let size = framebuffer.size();
+        let actual = framebuffer.to_rgb888();
+        let path = snapshot_path(name, "png");
+        if should_record(&path) {
+            let mut bytes = Vec::new();
+            let mut encoder = png::Encoder::new(&mut bytes, size.width, size.height);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder
+                .write_header()
+                .and_then(|mut writer| writer.write_image_data(&actual))
+                .expect("Failed to encode snapshot");
+            write_snapshot(&path, &bytes);
+            return;
+        }
+
+        let decoder = png::Decoder::new(File::open(&path).expect("Failed to open snapshot"));
+        let mut reader = decoder.read_info().expect("Failed to decode snapshot");
+        let mut expected = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut expected)
+            .expect("Failed to decode snapshot");
+        expected.truncate(info.buffer_size());
+
+        assert!(
+            (info.width, info.height) == (size.width, size.height) && expected == actual,
+            "snapshot `{name}` does not match {}",
+            path.display()
+        );
+    }
+}
+
+/// Create an empty directory for a [`FileStorage`](crate::storage::FileStorage), which is
+/// deleted with its contents when the returned [`TempDir`] is dropped.
+pub fn storage_dir() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("storage-")
+        .tempdir()
+        .expect("Failed to create storage directory")
+}
+
+/// Convert a buffer to plain text, one line per row.
+pub fn buffer_to_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            text.push_str(buffer[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn snapshot_path(name: &str, extension: &str) -> PathBuf {
+    let manifest_dir =
+        std::env::var_os("CARGO_MANIFEST_DIR").expect("Snapshots can only be used under cargo");
+    PathBuf::from(manifest_dir)
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.{extension}"))
+}
+
+#[track_caller]
+fn should_record(path: &Path) -> bool {
+    if std::env::var_os(UPDATE_SNAPSHOTS).is_some() {
+        return true;
+    }
+    assert!(
+        path.exists(),
+        "snapshot {} is missing, record it with {UPDATE_SNAPSHOTS}=1",
+        path.display()
+    );
+    false
+}
+
+fn write_snapshot(path: &Path, contents: &[u8]) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("Failed to create snapshot folder");
+    }
+    fs::write(path, contents).expect("Failed to write snapshot");
+    eprintln!("recorded snapshot {}", path.display());
+}
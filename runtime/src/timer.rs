@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+/// Identifier of a timer, chosen by the app.
+///
+/// Starting a timer with the id of a running one replaces it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(pub u32);
+
+/// When a timer fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timer {
+    /// Once, after the given time.
+    Once(Duration),
+    /// Every time the given interval passed, until it is cancelled.
+    Every(Duration),
+}
+
+/// A running timer.
+#[derive(Debug, Clone, Copy)]
+struct Scheduled {
+    id: TimerId,
+    due: Instant,
+    /// Interval of a repeating timer
+    every: Option<Duration>,
+}
+
+/// Timers of an app, which fire by the time of the event loop's clock.
+#[derive(Debug, Default, Clone)]
+pub struct Timers {
+    scheduled: Vec<Scheduled>,
+}
+
+impl Timers {
+    /// Start a timer at `now`, replacing a running one with the same id.
+    pub fn start(&mut self, id: TimerId, timer: Timer, now: Instant) {
+        self.cancel(id);
+        let (after, every) = match timer {
+            Timer::Once(after) => (after, None),
+            // A zero interval would fire in every iteration without ever waiting
+            Timer::Every(interval) => {
+                let interval = interval.max(Duration::from_millis(1));
+                (interval, Some(interval))
+            }
+        };
+        self.scheduled.push(Scheduled {
+            id,
+            due: now + after,
+            every,
+        });
+    }
+
+    /// Stop a timer, if it is running.
+    pub fn cancel(&mut self, id: TimerId) {
+        self.scheduled.retain(|scheduled| scheduled.id != id);
+    }
+
+    /// Check if a timer is running.
+    pub fn is_running(&self, id: TimerId) -> bool {
+        self.scheduled.iter().any(|scheduled| scheduled.id == id)
+    }
+
+    /// Get the time the next timer fires at.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.scheduled.iter().map(|scheduled| scheduled.due).min()
+    }
+
+    /// Remove the timers that are due at `now`, and return their ids in the order they were due.
+    ///
+    /// A repeating timer fires once, even if several intervals passed, and is due again at the
+    /// next multiple of its interval after `now`.
+    pub fn expire(&mut self, now: Instant) -> Vec<TimerId> {
+        let mut fired: Vec<_> = self
+            .scheduled
+            .iter()
+            .filter(|scheduled| scheduled.due <= now)
+            .map(|scheduled| (scheduled.due, scheduled.id))
+            .collect();
+        fired.sort();
+
+        self.scheduled.retain_mut(|scheduled| {
+            if scheduled.due > now {
+                return true;
+            }
+            let Some(every) = scheduled.every else {
+                return false;
+            };
+            let missed = now.duration_since(scheduled.due).as_nanos() / every.as_nanos();
+            scheduled.due += every * (missed as u32 + 1);
+            true
+        });
+        fired.into_iter().map(|(_, id)| id).collect()
+    }
+}
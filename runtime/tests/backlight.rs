@@ -0,0 +1,209 @@
+use std::time::{Duration, Instant};
+
+use mousefood::prelude::*;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::backlight::{BacklightConfig, IdleBacklight};
+use ratatui_mousefood_runtime::button::{Button, ButtonConfig, ButtonId, ButtonPressType};
+use ratatui_mousefood_runtime::clock::Clock;
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::event_loop::EventLoop;
+use ratatui_mousefood_runtime::testing::TestPlatform;
+
+const CONFIG: BacklightConfig = BacklightConfig {
+    brightness: 80,
+    dim_brightness: 20,
+    dim_after: Some(Duration::from_secs(10)),
+    off_after: Some(Duration::from_secs(60)),
+};
+
+#[test]
+fn dims_and_turns_off_while_idle() {
+    let start = Instant::now();
+    let mut backlight = IdleBacklight::new(start);
+
+    assert_eq!(backlight.brightness(&CONFIG, start), 80);
+    assert_eq!(
+        backlight.brightness(&CONFIG, start + Duration::from_secs(10)),
+        20
+    );
+    assert_eq!(
+        backlight.brightness(&CONFIG, start + Duration::from_secs(60)),
+        0
+    );
+    assert!(backlight.is_off(&CONFIG, start + Duration::from_secs(60)));
+
+    backlight.wake(start + Duration::from_secs(61));
+    assert_eq!(
+        backlight.brightness(&CONFIG, start + Duration::from_secs(62)),
+        80
+    );
+}
+
+#[test]
+fn never_dims_without_timeouts() {
+    let config = BacklightConfig {
+        dim_after: None,
+        off_after: None,
+        ..CONFIG
+    };
+    let start = Instant::now();
+    let backlight = IdleBacklight::new(start);
+    let later = start + Duration::from_secs(3600);
+    assert_eq!(backlight.brightness(&config, later), 80);
+}
+
+/// App that turns the backlight off right away and records the presses, and puts the
+/// backlight to sleep on a chord.
+#[derive(Default)]
+struct TestApp {
+    presses: Vec<Button>,
+    double_press: Option<Duration>,
+}
+
+impl App for TestApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, button: Button) -> Vec<Command> {
+        self.presses.push(button);
+        if button.is_chord() {
+            vec![Command::Sleep]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn backlight(&self) -> BacklightConfig {
+        BacklightConfig {
+            dim_after: None,
+            off_after: Some(Duration::from_millis(20)),
+            ..CONFIG
+        }
+    }
+
+    fn button_config(&self, _button: ButtonId) -> ButtonConfig {
+        ButtonConfig {
+            debounce: Duration::ZERO,
+            double_press: self.double_press,
+            ..ButtonConfig::default()
+        }
+    }
+}
+
+/// Press button 1 at `at` and release it 5ms later.
+fn click(event_loop: &mut EventLoop<TestPlatform>, app: &mut TestApp, at: Instant) {
+    event_loop
+        .platform_mut()
+        .click(ButtonId::Button1, at, Duration::from_millis(5));
+    event_loop.handle_buttons(app, Duration::ZERO);
+    event_loop.update_backlight(app);
+}
+
+#[test]
+fn press_wakes_backlight_without_reaching_app() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    event_loop.update_backlight(&app);
+    event_loop
+        .platform_mut()
+        .clock
+        .advance(Duration::from_millis(30));
+    event_loop.update_backlight(&app);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 0]);
+
+    let now = event_loop.platform_mut().clock.now();
+    click(&mut event_loop, &mut app, now);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 0, 80]);
+    assert!(app.presses.is_empty());
+
+    click(&mut event_loop, &mut app, now + Duration::from_millis(10));
+    assert_eq!(app.presses, [Button::Button1(ButtonPressType::Short)]);
+}
+
+#[test]
+fn set_backlight_holds_until_press() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    event_loop.update_backlight(&app);
+    event_loop.execute(&mut app, vec![Command::SetBacklight(30)]);
+    event_loop
+        .platform_mut()
+        .clock
+        .advance(Duration::from_millis(30));
+    event_loop.update_backlight(&app);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 30]);
+
+    // The press reaches the app, since the backlight is on
+    let now = event_loop.platform_mut().clock.now();
+    click(&mut event_loop, &mut app, now);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 30, 80]);
+    assert_eq!(app.presses, [Button::Button1(ButtonPressType::Short)]);
+}
+
+#[test]
+fn sleep_turns_backlight_off_until_press() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    event_loop.update_backlight(&app);
+    event_loop.execute(&mut app, vec![Command::Sleep]);
+    event_loop.update_backlight(&app);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 0]);
+
+    let now = event_loop.platform_mut().clock.now();
+    click(&mut event_loop, &mut app, now);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 0, 80]);
+    assert!(app.presses.is_empty());
+}
+
+#[test]
+fn sleep_from_chord_lasts_while_other_button_is_held() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    event_loop.update_backlight(&app);
+    let start = event_loop.platform_mut().clock.now();
+    event_loop
+        .platform_mut()
+        .push_edge(ButtonId::Button1, true, start)
+        .push_edge(ButtonId::Button2, true, start);
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+    // The buttons are released one at a time, in separate batches
+    let clock = event_loop.platform_mut().clock.clone();
+    clock.advance(Duration::from_secs(3));
+    let released = clock.now();
+    event_loop
+        .platform_mut()
+        .push_edge(ButtonId::Button1, false, released);
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+    event_loop.update_backlight(&app);
+    assert_eq!(app.presses, [Button::Both(ButtonPressType::Hold)]);
+
+    clock.advance(Duration::from_millis(100));
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+    event_loop.update_backlight(&app);
+    event_loop
+        .platform_mut()
+        .push_edge(ButtonId::Button2, false, clock.now());
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+    event_loop.update_backlight(&app);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 0]);
+    assert_eq!(app.presses.len(), 1);
+}
+
+#[test]
+fn press_that_wakes_backlight_is_not_delayed_to_app() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp {
+        double_press: Some(Duration::from_millis(300)),
+        ..TestApp::default()
+    };
+    event_loop.update_backlight(&app);
+    let clock = event_loop.platform_mut().clock.clone();
+    clock.advance(Duration::from_millis(30));
+    event_loop.update_backlight(&app);
+
+    click(&mut event_loop, &mut app, clock.now());
+    clock.advance(Duration::from_secs(1));
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+    assert_eq!(event_loop.platform_mut().backlight, [80, 0, 80]);
+    assert!(app.presses.is_empty());
+}
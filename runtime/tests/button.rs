@@ -0,0 +1,498 @@
+use std::time::{Duration, Instant};
+
+use mousefood::prelude::*;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::{
+    Button, ButtonConfig, ButtonEvent, ButtonId, ButtonPressType, ButtonState, RepeatConfig,
+};
+use ratatui_mousefood_runtime::clock::Clock;
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::event_loop::EventLoop;
+use ratatui_mousefood_runtime::testing::TestPlatform;
+
+/// App that records the presses and button events.
+#[derive(Default)]
+struct TestApp {
+    presses: Vec<Button>,
+    events: Vec<ButtonEvent>,
+    double_press: Option<Duration>,
+}
+
+impl App for TestApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, button: Button) -> Vec<Command> {
+        self.presses.push(button);
+        Vec::new()
+    }
+
+    fn handle_event(&mut self, event: ButtonEvent) -> Vec<Command> {
+        self.events.push(event);
+        vec![Command::Redraw]
+    }
+
+    fn button_config(&self, _button: ButtonId) -> ButtonConfig {
+        ButtonConfig {
+            double_press: self.double_press,
+            ..ButtonConfig::default()
+        }
+    }
+}
+
+#[test]
+fn classifies_presses_by_edge_times() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    let start = event_loop.platform_mut().clock.now();
+
+    // Both presses were queued up while the event loop was busy
+    event_loop
+        .platform_mut()
+        .push_edge(ButtonId::Button1, true, start)
+        .push_edge(ButtonId::Button1, false, start + Duration::from_millis(100))
+        .push_edge(ButtonId::Button2, true, start + Duration::from_millis(200))
+        .push_edge(ButtonId::Button2, false, start + Duration::from_millis(900));
+    event_loop.handle_buttons(&mut app, Duration::from_millis(50));
+
+    assert_eq!(
+        app.presses,
+        [
+            Button::Button1(ButtonPressType::Short),
+            Button::Button2(ButtonPressType::Long),
+        ]
+    );
+    assert!(event_loop.is_dirty());
+    assert_eq!(
+        event_loop.platform_mut().timeouts,
+        [
+            Duration::from_millis(50),
+            Duration::ZERO,
+            Duration::ZERO,
+            Duration::ZERO,
+            Duration::ZERO
+        ]
+    );
+}
+
+#[test]
+fn sends_down_and_up_before_press() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    let start = event_loop.platform_mut().clock.now();
+
+    event_loop
+        .platform_mut()
+        .click(ButtonId::Button1, start, Duration::from_millis(100));
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+    assert_eq!(
+        app.events,
+        [
+            ButtonEvent::Down(ButtonId::Button1),
+            ButtonEvent::Up(ButtonId::Button1)
+        ]
+    );
+    assert_eq!(app.presses, [Button::Button1(ButtonPressType::Short)]);
+}
+
+#[test]
+fn sends_hold_progress_while_held() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    let start = event_loop.platform_mut().clock.now();
+
+    event_loop
+        .platform_mut()
+        .push_edge(ButtonId::Button2, true, start)
+        .clock
+        .advance(Duration::from_millis(400));
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+    let [down, ButtonEvent::HoldProgress(button, held)] = app.events[..] else {
+        panic!("unexpected events {:?}", app.events);
+    };
+    assert_eq!(down, ButtonEvent::Down(ButtonId::Button2));
+    assert_eq!(button, ButtonId::Button2);
+    assert_eq!(held, Duration::from_millis(400));
+    assert!(app.presses.is_empty());
+    assert!(event_loop.is_dirty());
+}
+
+#[test]
+fn sends_no_events_for_bounces() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    let start = event_loop.platform_mut().clock.now();
+
+    event_loop
+        .platform_mut()
+        .push_edge(ButtonId::Button1, true, start)
+        .push_edge(ButtonId::Button1, false, start + Duration::from_millis(2))
+        .push_edge(ButtonId::Button1, true, start + Duration::from_millis(4))
+        .push_edge(ButtonId::Button1, false, start + Duration::from_millis(200))
+        .clock
+        .advance(Duration::from_millis(400));
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+    assert_eq!(
+        app.events,
+        [
+            ButtonEvent::Down(ButtonId::Button1),
+            ButtonEvent::Up(ButtonId::Button1)
+        ]
+    );
+}
+
+/// Feed the edges of both buttons at the given milliseconds from now to an event loop, and
+/// return the presses it forwarded.
+fn chord_presses(edges: &[(ButtonId, bool, u64)]) -> Vec<Button> {
+    chord_presses_with(TestApp::default(), edges)
+}
+
+/// Feed the edges of both buttons to an event loop like [`chord_presses`], with the given app.
+fn chord_presses_with(mut app: TestApp, edges: &[(ButtonId, bool, u64)]) -> Vec<Button> {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let start = event_loop.platform_mut().clock.now();
+    for &(button, pressed, ms) in edges {
+        event_loop
+            .platform_mut()
+            .push_edge(button, pressed, start + Duration::from_millis(ms));
+    }
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+    app.presses
+}
+
+#[test]
+fn fires_chord_once_when_released() {
+    let presses = chord_presses(&[
+        (ButtonId::Button1, true, 0),
+        (ButtonId::Button2, true, 50),
+        (ButtonId::Button2, false, 250),
+        (ButtonId::Button1, false, 300),
+    ]);
+    assert_eq!(presses, [Button::Both(ButtonPressType::Short)]);
+}
+
+#[test]
+fn fires_no_chord_while_both_are_held() {
+    let presses = chord_presses(&[(ButtonId::Button1, true, 0), (ButtonId::Button2, true, 0)]);
+    assert_eq!(presses, []);
+}
+
+#[test]
+fn orders_chords_by_first_button() {
+    let presses = chord_presses(&[
+        (ButtonId::Button1, true, 0),
+        (ButtonId::Button2, true, 300),
+        (ButtonId::Button2, false, 400),
+        (ButtonId::Button1, false, 450),
+    ]);
+    assert_eq!(presses, [Button::Button1Then2(ButtonPressType::Short)]);
+
+    let presses = chord_presses(&[
+        (ButtonId::Button2, true, 0),
+        (ButtonId::Button1, true, 300),
+        (ButtonId::Button2, false, 400),
+        (ButtonId::Button1, false, 450),
+    ]);
+    assert_eq!(presses, [Button::Button2Then1(ButtonPressType::Short)]);
+}
+
+#[test]
+fn classifies_chords_by_time_both_were_held() {
+    // Button 1 is held long before the chord, but the chord itself is long
+    let presses = chord_presses(&[
+        (ButtonId::Button1, true, 0),
+        (ButtonId::Button2, true, 2000),
+        (ButtonId::Button1, false, 2600),
+        (ButtonId::Button2, false, 2650),
+    ]);
+    assert_eq!(presses, [Button::Button1Then2(ButtonPressType::Long)]);
+}
+
+#[test]
+fn single_presses_resume_after_chord() {
+    let presses = chord_presses(&[
+        (ButtonId::Button1, true, 0),
+        (ButtonId::Button2, true, 0),
+        (ButtonId::Button1, false, 100),
+        (ButtonId::Button2, false, 150),
+        (ButtonId::Button2, true, 300),
+        (ButtonId::Button2, false, 400),
+    ]);
+    assert_eq!(
+        presses,
+        [
+            Button::Both(ButtonPressType::Short),
+            Button::Button2(ButtonPressType::Short),
+        ]
+    );
+}
+
+#[test]
+fn reports_short_press_before_chord_in_double_press_window() {
+    let app = TestApp {
+        double_press: Some(Duration::from_millis(300)),
+        ..TestApp::default()
+    };
+    // Button 1 is pressed again within the double press window, but as part of a chord
+    let presses = chord_presses_with(
+        app,
+        &[
+            (ButtonId::Button1, true, 0),
+            (ButtonId::Button1, false, 100),
+            (ButtonId::Button1, true, 200),
+            (ButtonId::Button2, true, 220),
+            (ButtonId::Button1, false, 400),
+            (ButtonId::Button2, false, 420),
+        ],
+    );
+    assert_eq!(
+        presses,
+        [
+            Button::Button1(ButtonPressType::Short),
+            Button::Both(ButtonPressType::Short),
+        ]
+    );
+}
+
+const CONFIG: ButtonConfig = ButtonConfig {
+    debounce: Duration::from_millis(20),
+    long_press: Duration::from_millis(300),
+    hold: Duration::from_millis(1000),
+    double_press: None,
+    repeat: None,
+};
+
+/// Feed edges at the given milliseconds after `start` to a button, alternating between
+/// pressed and released, and return the detected presses.
+fn presses(start: Instant, edges: &[u64]) -> Vec<ButtonPressType> {
+    let mut state = ButtonState::default();
+    let mut presses = Vec::new();
+    for (i, &ms) in edges.iter().enumerate() {
+        let at = start + Duration::from_millis(ms);
+        state.update(&CONFIG, i % 2 == 0, at, |press_type| {
+            presses.push(press_type)
+        });
+    }
+    presses
+}
+
+#[test]
+fn classifies_presses_by_config() {
+    let start = Instant::now();
+    assert_eq!(presses(start, &[0, 299]), [ButtonPressType::Short]);
+    assert_eq!(presses(start, &[0, 300]), [ButtonPressType::Long]);
+    assert_eq!(presses(start, &[0, 999]), [ButtonPressType::Long]);
+    assert_eq!(presses(start, &[0, 5000]), [ButtonPressType::Hold]);
+}
+
+#[test]
+fn ignores_bounces() {
+    let start = Instant::now();
+    // The contacts bounce after pressing and after releasing
+    assert_eq!(
+        presses(start, &[0, 3, 5, 400, 402, 410]),
+        [ButtonPressType::Long]
+    );
+}
+
+#[test]
+fn applies_last_edge_after_debounce_time() {
+    let start = Instant::now();
+    let mut state = ButtonState::default();
+    let mut presses = Vec::new();
+    state.update(&CONFIG, true, start, |_| {});
+    state.update(&CONFIG, false, start + Duration::from_millis(10), |_| {});
+    assert!(state.is_pressed());
+
+    state.settle(&CONFIG, start + Duration::from_millis(15), |press_type| {
+        presses.push(press_type)
+    });
+    assert!(state.is_pressed());
+    state.settle(&CONFIG, start + Duration::from_millis(20), |press_type| {
+        presses.push(press_type)
+    });
+    assert!(!state.is_pressed());
+    assert_eq!(presses, [ButtonPressType::Short]);
+}
+
+/// App that reads its long-press threshold from its settings.
+struct ConfiguredApp {
+    long_press: Duration,
+    presses: Vec<Button>,
+}
+
+impl App for ConfiguredApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, button: Button) -> Vec<Command> {
+        self.presses.push(button);
+        Vec::new()
+    }
+
+    fn button_config(&self, button: ButtonId) -> ButtonConfig {
+        match button {
+            ButtonId::Button1 => ButtonConfig {
+                long_press: self.long_press,
+                ..ButtonConfig::default()
+            },
+            ButtonId::Button2 => ButtonConfig::default(),
+        }
+    }
+}
+
+#[test]
+fn uses_config_of_each_button() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = ConfiguredApp {
+        long_press: Duration::from_millis(200),
+        presses: Vec::new(),
+    };
+    let start = event_loop.platform_mut().clock.now();
+
+    event_loop
+        .platform_mut()
+        .click(ButtonId::Button1, start, Duration::from_millis(300))
+        .click(
+            ButtonId::Button2,
+            start + Duration::from_secs(1),
+            Duration::from_millis(300),
+        )
+        .click(
+            ButtonId::Button2,
+            start + Duration::from_secs(2),
+            Duration::from_secs(3),
+        );
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+    assert_eq!(
+        app.presses,
+        [
+            Button::Button1(ButtonPressType::Long),
+            Button::Button2(ButtonPressType::Short),
+            Button::Button2(ButtonPressType::Hold),
+        ]
+    );
+}
+
+/// Button whose edges and settle calls happen at given milliseconds after a fixed start.
+struct TestButton {
+    state: ButtonState,
+    config: ButtonConfig,
+    start: Instant,
+    presses: Vec<ButtonPressType>,
+}
+
+impl TestButton {
+    fn new(config: ButtonConfig) -> Self {
+        Self {
+            state: ButtonState::default(),
+            config,
+            start: Instant::now(),
+            presses: Vec::new(),
+        }
+    }
+
+    fn at(&self, ms: u64) -> Instant {
+        self.start + Duration::from_millis(ms)
+    }
+
+    fn edge(&mut self, pressed: bool, ms: u64) -> &mut Self {
+        let at = self.at(ms);
+        let presses = &mut self.presses;
+        self.state.update(&self.config, pressed, at, |press_type| {
+            presses.push(press_type)
+        });
+        self
+    }
+
+    fn click(&mut self, from_ms: u64, to_ms: u64) -> &mut Self {
+        self.edge(true, from_ms).edge(false, to_ms)
+    }
+
+    fn settle(&mut self, ms: u64) -> &mut Self {
+        let now = self.at(ms);
+        let presses = &mut self.presses;
+        self.state
+            .settle(&self.config, now, |press_type| presses.push(press_type));
+        self
+    }
+}
+
+const DOUBLE_PRESS: ButtonConfig = ButtonConfig {
+    double_press: Some(Duration::from_millis(300)),
+    ..CONFIG
+};
+
+#[test]
+fn detects_double_press() {
+    let mut button = TestButton::new(DOUBLE_PRESS);
+    button.click(0, 100).click(200, 300).settle(1000);
+    assert_eq!(button.presses, [ButtonPressType::Double]);
+}
+
+#[test]
+fn reports_short_press_after_double_press_window() {
+    let mut button = TestButton::new(DOUBLE_PRESS);
+    button.click(0, 100);
+    assert_eq!(button.state.deadline(&DOUBLE_PRESS), Some(button.at(400)));
+
+    button.settle(399);
+    assert!(button.presses.is_empty());
+    button.settle(400);
+    assert_eq!(button.presses, [ButtonPressType::Short]);
+
+    // Too late for a double press
+    button.click(500, 600).click(900, 1000).settle(1300);
+    assert_eq!(button.presses, [ButtonPressType::Short; 3]);
+}
+
+#[test]
+fn long_second_press_is_no_double_press() {
+    let mut button = TestButton::new(DOUBLE_PRESS);
+    button.click(0, 100).click(200, 700);
+    assert_eq!(
+        button.presses,
+        [ButtonPressType::Short, ButtonPressType::Long]
+    );
+}
+
+#[test]
+fn repeats_faster_while_held() {
+    let config = ButtonConfig {
+        repeat: Some(RepeatConfig {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(200),
+            min_interval: Duration::from_millis(40),
+            acceleration: 50,
+        }),
+        ..CONFIG
+    };
+    let mut button = TestButton::new(config);
+    button.edge(true, 0);
+    assert_eq!(button.state.deadline(&config), Some(button.at(500)));
+
+    // Repeats at 500, 700, 800, 850, 890, 930 and 970ms
+    button.settle(499);
+    assert!(button.presses.is_empty());
+    button.settle(1000);
+    assert_eq!(button.presses, [ButtonPressType::Repeat; 7]);
+    assert_eq!(button.state.deadline(&config), Some(button.at(1010)));
+
+    // The release does not report the press once more
+    button.edge(false, 1005).settle(2000);
+    assert_eq!(button.presses.len(), 7);
+    assert_eq!(button.state.deadline(&config), None);
+}
+
+#[test]
+fn short_press_does_not_repeat() {
+    let config = ButtonConfig {
+        repeat: Some(RepeatConfig::default()),
+        ..CONFIG
+    };
+    let mut button = TestButton::new(config);
+    button.click(0, 100).settle(1000);
+    assert_eq!(button.presses, [ButtonPressType::Short]);
+}
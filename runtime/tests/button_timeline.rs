@@ -0,0 +1,259 @@
+use std::time::{Duration, Instant};
+
+use mousefood::prelude::*;
+use proptest::prelude::*;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::backlight::BacklightConfig;
+use ratatui_mousefood_runtime::button::{
+    Button, ButtonConfig, ButtonId, ButtonPressType, ButtonState, RepeatConfig,
+};
+use ratatui_mousefood_runtime::clock::Clock;
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::event_loop::EventLoop;
+use ratatui_mousefood_runtime::testing::TestPlatform;
+
+const CONFIG: ButtonConfig = ButtonConfig {
+    debounce: Duration::from_millis(20),
+    long_press: Duration::from_millis(300),
+    hold: Duration::from_millis(1000),
+    double_press: None,
+    repeat: None,
+};
+
+/// Milliseconds between the edges of a button, which alternate between pressed and released.
+fn timeline(min_gap: u64) -> impl Strategy<Value = Vec<u64>> {
+    prop::collection::vec(min_gap..2000, 0..20)
+}
+
+/// Edges of both buttons, as the button that goes down or up and the milliseconds since the
+/// last edge of either button.
+///
+/// Most gaps are short, so presses overlap and fall into the double press window, but some
+/// are long enough for the backlight to turn off.
+fn chord_timeline() -> impl Strategy<Value = Vec<(ButtonId, u64)>> {
+    let button = prop_oneof![Just(ButtonId::Button1), Just(ButtonId::Button2)];
+    let gap = prop_oneof![4 => 0..400u64, 1 => 0..2000u64];
+    prop::collection::vec((button, gap), 0..30)
+}
+
+/// [`CONFIG`] with or without double presses, and with or without repeats.
+fn config() -> impl Strategy<Value = ButtonConfig> {
+    let double_press = prop::option::of(100..500u64);
+    let repeat_delay = prop::option::of(100..1500u64);
+    (double_press, repeat_delay).prop_map(|(double_press, repeat_delay)| ButtonConfig {
+        double_press: double_press.map(Duration::from_millis),
+        repeat: repeat_delay.map(|delay| RepeatConfig {
+            delay: Duration::from_millis(delay),
+            ..RepeatConfig::default()
+        }),
+        ..CONFIG
+    })
+}
+
+/// Feed edges with the given gaps to a button, settle it long after the last one, and return
+/// the detected presses.
+fn classify(gaps: &[u64]) -> Vec<ButtonPressType> {
+    classify_with(&CONFIG, gaps)
+}
+
+/// Classify the presses like [`classify`], with the given config.
+fn classify_with(config: &ButtonConfig, gaps: &[u64]) -> Vec<ButtonPressType> {
+    let mut state = ButtonState::default();
+    let mut presses = Vec::new();
+    let mut at = Instant::now();
+    for (i, &gap) in gaps.iter().enumerate() {
+        at += Duration::from_millis(gap);
+        state.update(config, i % 2 == 0, at, |press_type| {
+            presses.push(press_type)
+        });
+    }
+    state.settle(config, at + Duration::from_secs(10), |press_type| {
+        presses.push(press_type)
+    });
+    presses
+}
+
+/// Feed the edges of both buttons to an event loop, with the backlight turning off while they
+/// are idle, release them, and return the presses forwarded to the app.
+///
+/// A double press is counted as the two short presses it is made of, and the presses are
+/// sorted, since a short press can be delayed past the presses of the other button.
+fn forward_chords(config: ButtonConfig, edges: &[(ButtonId, u64)]) -> Vec<Button> {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp {
+        config,
+        backlight: BacklightConfig {
+            dim_after: None,
+            off_after: Some(Duration::from_millis(1500)),
+            ..BacklightConfig::default()
+        },
+        ..TestApp::default()
+    };
+    let mut at = event_loop.platform_mut().clock.now();
+    let mut pressed = [false; 2];
+    for &(button, gap) in edges {
+        at += Duration::from_millis(gap);
+        let pressed = &mut pressed[button as usize];
+        *pressed = !*pressed;
+        event_loop.platform_mut().push_edge(button, *pressed, at);
+    }
+    // Complete the presses, since the first half of a double press waits for the second
+    for (button, pressed) in [ButtonId::Button1, ButtonId::Button2]
+        .into_iter()
+        .zip(pressed)
+    {
+        if pressed {
+            at += Duration::from_millis(100);
+            event_loop.platform_mut().push_edge(button, false, at);
+        }
+    }
+    event_loop
+        .platform_mut()
+        .clock
+        .advance_to(at + Duration::from_secs(10));
+    event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+    let mut presses: Vec<_> = app
+        .presses
+        .into_iter()
+        .flat_map(|button| match button {
+            Button::Button1(ButtonPressType::Double) => {
+                vec![Button::Button1(ButtonPressType::Short); 2]
+            }
+            Button::Button2(ButtonPressType::Double) => {
+                vec![Button::Button2(ButtonPressType::Short); 2]
+            }
+            button => vec![button],
+        })
+        .collect();
+    presses.sort_by_key(|button| format!("{button:?}"));
+    presses
+}
+
+/// App that records the presses.
+#[derive(Default)]
+struct TestApp {
+    presses: Vec<Button>,
+    config: ButtonConfig,
+    backlight: BacklightConfig,
+}
+
+impl App for TestApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, button: Button) -> Vec<Command> {
+        self.presses.push(button);
+        Vec::new()
+    }
+
+    fn backlight(&self) -> BacklightConfig {
+        self.backlight
+    }
+
+    fn button_config(&self, _button: ButtonId) -> ButtonConfig {
+        self.config
+    }
+}
+
+proptest! {
+    #[test]
+    fn reports_at_most_one_press_per_press_edge(gaps in timeline(0)) {
+        let presses = classify(&gaps);
+        prop_assert!(presses.len() <= gaps.len().div_ceil(2));
+    }
+
+    #[test]
+    fn classifies_presses_by_time_held(gaps in timeline(20)) {
+        let expected: Vec<_> = gaps
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .map(|&held| CONFIG.press_type(Duration::from_millis(held)))
+            .collect();
+        prop_assert_eq!(classify(&gaps), expected);
+    }
+
+    #[test]
+    fn bounces_do_not_change_presses(
+        gaps in timeline(40),
+        bounces in prop::collection::vec(prop::option::of((1..5u64, 1..5u64)), 20),
+    ) {
+        // Every edge can be followed by the contacts opening and closing again
+        let mut bouncing_gaps = Vec::new();
+        let mut bounced = 0;
+        for (&gap, bounce) in gaps.iter().zip(&bounces) {
+            bouncing_gaps.push(gap - bounced);
+            bounced = 0;
+            if let Some((open, close)) = *bounce {
+                bouncing_gaps.extend([open, close]);
+                bounced = open + close;
+            }
+        }
+        prop_assert_eq!(classify(&bouncing_gaps), classify(&gaps));
+    }
+
+    #[test]
+    fn event_loop_reports_presses_of_button_state(gaps in timeline(0)) {
+        let mut event_loop = EventLoop::new(TestPlatform::default());
+        let mut app = TestApp {
+            config: CONFIG,
+            ..TestApp::default()
+        };
+        let mut at = event_loop.platform_mut().clock.now();
+        for (i, &gap) in gaps.iter().enumerate() {
+            at += Duration::from_millis(gap);
+            event_loop.platform_mut().push_edge(ButtonId::Button1, i % 2 == 0, at);
+        }
+        event_loop
+            .platform_mut()
+            .clock
+            .advance_to(at + Duration::from_secs(10));
+        event_loop.handle_buttons(&mut app, Duration::ZERO);
+
+        let expected: Vec<_> = classify(&gaps)
+            .into_iter()
+            .map(Button::Button1)
+            .collect();
+        prop_assert_eq!(app.presses, expected);
+    }
+
+    #[test]
+    fn reports_every_press_once(config in config(), gaps in timeline(20)) {
+        // Only complete presses, since the first half of a double press waits for the second
+        let gaps = &gaps[..gaps.len() / 2 * 2];
+        let repeats = |held: u64| {
+            config
+                .repeat
+                .is_some_and(|repeat| Duration::from_millis(held) >= repeat.delay)
+        };
+        let held: Vec<u64> = gaps.iter().skip(1).step_by(2).copied().collect();
+        let presses = classify_with(&config, gaps);
+
+        // A press is reported as a repeat once it is held long enough, and otherwise on its own
+        // or as half of a double press
+        let count = |press_type| presses.iter().filter(|&&press| press == press_type).count();
+        let single = count(ButtonPressType::Short)
+            + count(ButtonPressType::Long)
+            + count(ButtonPressType::Hold)
+            + 2 * count(ButtonPressType::Double);
+        let repeating = held.iter().filter(|&&held| repeats(held)).count();
+        prop_assert_eq!(single, held.len() - repeating);
+        prop_assert!(count(ButtonPressType::Repeat) >= repeating);
+        if config.double_press.is_none() {
+            prop_assert_eq!(count(ButtonPressType::Double), 0);
+        }
+    }
+
+    #[test]
+    fn double_presses_only_combine_short_presses(
+        config in config(),
+        edges in chord_timeline(),
+    ) {
+        // Neither chords nor a press that turns the backlight on may swallow or add a press
+        let single = ButtonConfig {
+            double_press: None,
+            ..config
+        };
+        prop_assert_eq!(forward_chords(config, &edges), forward_chords(single, &edges));
+    }
+}
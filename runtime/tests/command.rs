@@ -0,0 +1,114 @@
+use std::fs;
+use std::time::Duration;
+
+use mousefood::prelude::*;
+use mousefood::ratatui::backend::TestBackend;
+use ratatui_mousefood_runtime::app::{App, Context};
+use ratatui_mousefood_runtime::button::{Button, ButtonId};
+use ratatui_mousefood_runtime::clock::Clock;
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::event_loop::EventLoop;
+use ratatui_mousefood_runtime::storage::{FileStorage, Namespace, StorageError, shared};
+use ratatui_mousefood_runtime::testing::{TestPlatform, storage_dir};
+
+/// App that quits on the first press.
+struct QuitApp;
+
+impl App for QuitApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, _button: Button) -> Vec<Command> {
+        vec![Command::Quit]
+    }
+}
+
+/// App that persists a namespace on every update, and records the failures.
+struct PersistApp {
+    namespace: Namespace,
+    errors: Vec<String>,
+}
+
+impl App for PersistApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, _button: Button) -> Vec<Command> {
+        Vec::new()
+    }
+
+    fn update(&mut self, _ctx: &Context, _dt: Duration) -> Vec<Command> {
+        vec![Command::Persist(self.namespace.clone())]
+    }
+
+    fn on_persist_error(&mut self, namespace: &Namespace, error: StorageError) -> Vec<Command> {
+        self.errors.push(format!("{}: {error}", namespace.name()));
+        vec![Command::Redraw]
+    }
+}
+
+#[test]
+fn run_returns_after_quit() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let now = event_loop.platform_mut().clock.now();
+    event_loop
+        .platform_mut()
+        .click(ButtonId::Button1, now, Duration::from_millis(50));
+    let mut terminal = Terminal::new(TestBackend::new(40, 13)).unwrap();
+
+    event_loop.run(&mut QuitApp, &mut terminal).unwrap();
+}
+
+#[test]
+fn quit_is_recorded() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    assert!(!event_loop.has_quit());
+    event_loop.execute(&mut QuitApp, vec![Command::Redraw, Command::Quit]);
+    assert!(event_loop.has_quit());
+}
+
+#[test]
+fn persist_commits_namespace() {
+    let dir = storage_dir();
+    let storage = shared(FileStorage::new(dir.path()));
+    let namespace = Namespace::new(&storage, "app");
+    namespace.put("count", &3_u32).unwrap();
+
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    event_loop.execute(&mut QuitApp, vec![Command::Persist(namespace)]);
+
+    let storage = shared(FileStorage::new(dir.path()));
+    let count: Option<u32> = Namespace::new(&storage, "app").get("count").unwrap();
+    assert_eq!(count, Some(3));
+}
+
+#[test]
+fn failed_persist_is_handed_to_app_and_loop_continues() {
+    let dir = storage_dir();
+    let path = dir.path().join("storage");
+    let storage = shared(FileStorage::new(&path));
+    let namespace = Namespace::new(&storage, "app");
+    namespace.put("count", &3_u32).unwrap();
+    // The storage directory cannot be created where a file is
+    fs::write(&path, "").unwrap();
+
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut terminal = Terminal::new(TestBackend::new(40, 13)).unwrap();
+    let mut app = PersistApp {
+        namespace,
+        errors: Vec::new(),
+    };
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    assert_eq!(app.errors.len(), 1);
+    assert!(app.errors[0].starts_with("app: "));
+
+    // The changes stay staged, and are committed once the storage works again
+    fs::remove_file(&path).unwrap();
+    event_loop
+        .platform_mut()
+        .clock
+        .advance(Duration::from_secs(1));
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    assert_eq!(app.errors.len(), 1);
+    let storage = shared(FileStorage::new(&path));
+    let count: Option<u32> = Namespace::new(&storage, "app").get("count").unwrap();
+    assert_eq!(count, Some(3));
+}
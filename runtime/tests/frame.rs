@@ -0,0 +1,163 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use mousefood::prelude::*;
+use mousefood::ratatui::backend::TestBackend;
+use ratatui_mousefood_runtime::app::{App, Context};
+use ratatui_mousefood_runtime::backlight::BacklightConfig;
+use ratatui_mousefood_runtime::button::{Button, ButtonId};
+use ratatui_mousefood_runtime::clock::Clock;
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::event_loop::EventLoop;
+use ratatui_mousefood_runtime::frame::{FrameConfig, FrameStats};
+use ratatui_mousefood_runtime::testing::TestPlatform;
+
+/// App that counts its frames, and changes on every press and on every update while `busy`
+/// is set.
+struct TestApp {
+    frames: Cell<u32>,
+    busy: bool,
+    config: FrameConfig,
+}
+
+impl TestApp {
+    fn new(max_fps: u32) -> Self {
+        Self {
+            frames: Cell::new(0),
+            busy: false,
+            config: FrameConfig {
+                max_fps,
+                tick_interval: Duration::from_millis(5),
+            },
+        }
+    }
+}
+
+impl App for TestApp {
+    fn draw(&self, _frame: &mut Frame) {
+        self.frames.set(self.frames.get() + 1);
+    }
+
+    fn handle_press(&mut self, _button: Button) -> Vec<Command> {
+        vec![Command::Redraw]
+    }
+
+    fn update(&mut self, _ctx: &Context, _dt: Duration) -> Vec<Command> {
+        if self.busy {
+            vec![Command::Redraw]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn backlight(&self) -> BacklightConfig {
+        BacklightConfig {
+            dim_after: None,
+            off_after: None,
+            ..BacklightConfig::default()
+        }
+    }
+
+    fn frame_config(&self) -> FrameConfig {
+        self.config
+    }
+}
+
+fn terminal() -> Terminal<TestBackend> {
+    Terminal::new(TestBackend::new(40, 13)).unwrap()
+}
+
+#[test]
+fn redraws_only_after_changes() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::new(0);
+    let mut terminal = terminal();
+
+    for _ in 0..3 {
+        event_loop.run_once(&mut app, &mut terminal).unwrap();
+    }
+    assert_eq!(app.frames.get(), 1);
+    assert_eq!(
+        event_loop.platform_mut().timeouts,
+        [Duration::from_millis(5); 3]
+    );
+
+    // The press is handled at the end of an iteration and drawn in the next one
+    let now = event_loop.platform_mut().clock.now();
+    event_loop
+        .platform_mut()
+        .click(ButtonId::Button1, now, Duration::from_millis(50));
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    assert_eq!(app.frames.get(), 2);
+
+    // Updates only run once the tick interval passed
+    app.busy = true;
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    assert_eq!(app.frames.get(), 2);
+    for _ in 0..2 {
+        event_loop
+            .platform_mut()
+            .clock
+            .advance(Duration::from_millis(5));
+        event_loop.run_once(&mut app, &mut terminal).unwrap();
+    }
+    assert_eq!(app.frames.get(), 4);
+    assert!(!event_loop.is_dirty());
+}
+
+#[test]
+fn limits_frame_rate() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::new(1);
+    app.busy = true;
+    let mut terminal = terminal();
+
+    for _ in 0..10 {
+        event_loop.run_once(&mut app, &mut terminal).unwrap();
+        event_loop
+            .platform_mut()
+            .clock
+            .advance(Duration::from_millis(5));
+    }
+    assert_eq!(app.frames.get(), 1);
+    assert!(event_loop.is_dirty());
+    assert_eq!(event_loop.frame_stats().frames, 1);
+
+    event_loop
+        .platform_mut()
+        .clock
+        .advance(Duration::from_secs(1));
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    assert_eq!(app.frames.get(), 2);
+}
+
+#[test]
+fn waits_until_next_frame_is_due() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::new(10);
+    app.config.tick_interval = Duration::from_secs(1);
+    let mut terminal = terminal();
+
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    event_loop.execute(&mut app, vec![Command::Redraw]);
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    let timeouts = &event_loop.platform_mut().timeouts;
+    assert_eq!(timeouts[0], Duration::from_secs(1));
+    assert_eq!(timeouts[1], Duration::from_millis(100));
+}
+
+#[test]
+fn records_frame_times() {
+    let mut stats = FrameStats::default();
+    stats.record(Duration::from_millis(8), Duration::from_millis(6));
+    stats.record(Duration::from_millis(16), Duration::from_millis(6));
+
+    assert_eq!(stats.frames, 2);
+    assert_eq!(stats.last_frame, Duration::from_millis(16));
+    assert_eq!(stats.last_flush, Duration::from_millis(6));
+    assert_eq!(stats.average_frame, Duration::from_millis(9));
+    assert_eq!(stats.average_flush, Duration::from_millis(6));
+    assert_eq!(stats.max_frame, Duration::from_millis(16));
+}
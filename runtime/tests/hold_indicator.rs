@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use mousefood::ratatui::buffer::Buffer;
+use mousefood::ratatui::layout::Rect;
+use mousefood::ratatui::style::Color;
+use mousefood::ratatui::widgets::Widget;
+use ratatui_mousefood_runtime::button::{ButtonConfig, ButtonEvent, ButtonId};
+use ratatui_mousefood_runtime::hold_indicator::HoldIndicator;
+
+const CONFIG: ButtonConfig = ButtonConfig {
+    debounce: Duration::from_millis(20),
+    long_press: Duration::from_millis(400),
+    hold: Duration::from_millis(1000),
+    double_press: None,
+    repeat: None,
+};
+
+/// Render the indicator into a single line of 10 cells.
+fn render(indicator: &HoldIndicator) -> Buffer {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 1));
+    indicator.render(buffer.area, &mut buffer);
+    buffer
+}
+
+fn hold(indicator: &mut HoldIndicator, button: ButtonId, millis: u64) -> bool {
+    let held = Duration::from_millis(millis);
+    indicator.handle_event(ButtonEvent::HoldProgress(button, held), &CONFIG)
+}
+
+#[test]
+fn fills_while_held() {
+    let mut indicator = HoldIndicator::default();
+    assert_eq!(render(&indicator), Buffer::with_lines(["          "]));
+
+    assert!(indicator.handle_event(ButtonEvent::Down(ButtonId::Button1), &CONFIG));
+    assert!(hold(&mut indicator, ButtonId::Button1, 200));
+    let buffer = render(&indicator);
+    assert_eq!(buffer, {
+        let mut expected = Buffer::with_lines(["━━━━━     "]);
+        expected.set_style(Rect::new(0, 0, 5, 1), Color::Cyan);
+        expected
+    });
+    assert!(!indicator.is_long_press());
+
+    assert!(indicator.handle_event(ButtonEvent::Up(ButtonId::Button1), &CONFIG));
+    assert_eq!(indicator.held(), None);
+    assert_eq!(render(&indicator), Buffer::with_lines(["          "]));
+}
+
+#[test]
+fn changes_color_at_long_press_threshold() {
+    let mut indicator = HoldIndicator::default();
+    indicator.handle_event(ButtonEvent::Down(ButtonId::Button2), &CONFIG);
+    hold(&mut indicator, ButtonId::Button2, 450);
+
+    assert!(indicator.is_long_press());
+    let mut expected = Buffer::with_lines(["━━━━━━━━━━"]);
+    expected.set_style(Rect::new(0, 0, 10, 1), Color::Green);
+    assert_eq!(render(&indicator), expected);
+}
+
+#[test]
+fn follows_first_button_only() {
+    let mut indicator = HoldIndicator::default();
+    indicator.handle_event(ButtonEvent::Down(ButtonId::Button1), &CONFIG);
+    assert!(!indicator.handle_event(ButtonEvent::Down(ButtonId::Button2), &CONFIG));
+    assert!(!hold(&mut indicator, ButtonId::Button2, 300));
+    assert!(!indicator.handle_event(ButtonEvent::Up(ButtonId::Button2), &CONFIG));
+    assert_eq!(indicator.held(), Some((ButtonId::Button1, Duration::ZERO)));
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+use ratatui_mousefood_runtime::storage::{FileStorage, Namespace, Storage, shared};
+use ratatui_mousefood_runtime::testing::storage_dir;
+
+#[test]
+fn keeps_committed_values() {
+    let dir = storage_dir();
+    let mut storage = FileStorage::new(dir.path());
+    storage.put("app", "name", "Ratatouille").unwrap();
+    storage.put("app", "scores", &vec![1_u16, 2, 3]).unwrap();
+    storage.commit("app").unwrap();
+
+    let mut storage = FileStorage::new(dir.path());
+    let name: Option<String> = storage.get("app", "name").unwrap();
+    let scores: Option<Vec<u16>> = storage.get("app", "scores").unwrap();
+    assert_eq!(name.as_deref(), Some("Ratatouille"));
+    assert_eq!(scores, Some(vec![1, 2, 3]));
+}
+
+#[test]
+fn stages_changes_until_commit() {
+    let dir = storage_dir();
+    let mut storage = FileStorage::new(dir.path());
+    storage.put("app", "count", &1_u32).unwrap();
+    storage.commit("app").unwrap();
+    storage.put("app", "count", &2_u32).unwrap();
+    storage.remove("app", "missing").unwrap();
+
+    // Staged changes are visible right away, but are lost without a commit
+    assert_eq!(storage.get::<u32>("app", "count").unwrap(), Some(2));
+    let mut restarted = FileStorage::new(dir.path());
+    assert_eq!(restarted.get::<u32>("app", "count").unwrap(), Some(1));
+}
+
+#[test]
+fn separates_namespaces() {
+    let dir = storage_dir();
+    let storage = shared(FileStorage::new(dir.path()));
+    let first = Namespace::new(&storage, "first");
+    let second = Namespace::new(&storage, "second");
+    first.put("key", &1_u8).unwrap();
+    second.put("key", &2_u8).unwrap();
+    first.commit().unwrap();
+
+    let storage = shared(FileStorage::new(dir.path()));
+    assert_eq!(
+        Namespace::new(&storage, "first").get("key").unwrap(),
+        Some(1_u8)
+    );
+    assert_eq!(
+        Namespace::new(&storage, "second").get::<u8>("key").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn removes_values() {
+    let dir = storage_dir();
+    let storage = shared(FileStorage::new(dir.path()));
+    let namespace = Namespace::new(&storage, "app");
+    namespace.put("key", &true).unwrap();
+    namespace.commit().unwrap();
+    namespace.remove("key").unwrap();
+    namespace.commit().unwrap();
+
+    let storage = shared(FileStorage::new(dir.path()));
+    assert_eq!(
+        Namespace::new(&storage, "app").get::<bool>("key").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn discards_invalid_namespace() {
+    let dir = storage_dir();
+    fs::write(dir.path().join("app.bin"), [0xFF, 0xFF, 0xFF]).unwrap();
+
+    let mut storage = FileStorage::new(dir.path());
+    assert_eq!(storage.get::<u8>("app", "key").unwrap(), None);
+}
+
+#[test]
+fn reports_values_of_another_type() {
+    let dir = storage_dir();
+    let mut storage = FileStorage::new(dir.path());
+    storage.put("app", "key", &true).unwrap();
+    assert!(storage.get::<String>("app", "key").is_err());
+}
@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+use mousefood::prelude::*;
+use mousefood::ratatui::backend::TestBackend;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::Button;
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::event_loop::EventLoop;
+use ratatui_mousefood_runtime::testing::TestPlatform;
+use ratatui_mousefood_runtime::timer::{Timer, TimerId, Timers};
+
+const RESCAN: TimerId = TimerId(1);
+const CLEAR_STATUS: TimerId = TimerId(2);
+
+#[test]
+fn one_shot_timer_fires_once() {
+    let start = Instant::now();
+    let mut timers = Timers::default();
+    timers.start(CLEAR_STATUS, Timer::Once(Duration::from_secs(3)), start);
+
+    assert_eq!(timers.deadline(), Some(start + Duration::from_secs(3)));
+    assert!(
+        timers
+            .expire(start + Duration::from_millis(2999))
+            .is_empty()
+    );
+    assert_eq!(
+        timers.expire(start + Duration::from_secs(3)),
+        [CLEAR_STATUS]
+    );
+    assert!(!timers.is_running(CLEAR_STATUS));
+    assert!(timers.expire(start + Duration::from_secs(10)).is_empty());
+}
+
+#[test]
+fn repeating_timer_keeps_its_phase() {
+    let start = Instant::now();
+    let mut timers = Timers::default();
+    timers.start(RESCAN, Timer::Every(Duration::from_secs(10)), start);
+
+    assert_eq!(timers.expire(start + Duration::from_secs(10)), [RESCAN]);
+    assert_eq!(timers.deadline(), Some(start + Duration::from_secs(20)));
+
+    // Missed intervals fire only once
+    assert_eq!(timers.expire(start + Duration::from_secs(45)), [RESCAN]);
+    assert_eq!(timers.deadline(), Some(start + Duration::from_secs(50)));
+}
+
+#[test]
+fn restart_replaces_and_cancel_stops_timer() {
+    let start = Instant::now();
+    let mut timers = Timers::default();
+    timers.start(CLEAR_STATUS, Timer::Once(Duration::from_secs(3)), start);
+    timers.start(
+        CLEAR_STATUS,
+        Timer::Once(Duration::from_secs(3)),
+        start + Duration::from_secs(2),
+    );
+    assert!(timers.expire(start + Duration::from_secs(4)).is_empty());
+    assert_eq!(
+        timers.expire(start + Duration::from_secs(5)),
+        [CLEAR_STATUS]
+    );
+
+    timers.start(RESCAN, Timer::Every(Duration::from_secs(10)), start);
+    timers.cancel(RESCAN);
+    assert_eq!(timers.deadline(), None);
+}
+
+#[test]
+fn fires_timers_in_order_they_were_due() {
+    let start = Instant::now();
+    let mut timers = Timers::default();
+    timers.start(RESCAN, Timer::Every(Duration::from_secs(2)), start);
+    timers.start(CLEAR_STATUS, Timer::Once(Duration::from_secs(1)), start);
+    assert_eq!(
+        timers.expire(start + Duration::from_secs(2)),
+        [CLEAR_STATUS, RESCAN]
+    );
+}
+
+/// App that starts a rescan timer on a press, and records the timers it receives.
+#[derive(Default)]
+struct TestApp {
+    fired: Vec<TimerId>,
+}
+
+impl App for TestApp {
+    fn draw(&self, _frame: &mut Frame) {}
+
+    fn handle_press(&mut self, _button: Button) -> Vec<Command> {
+        Vec::new()
+    }
+
+    fn on_timer(&mut self, id: TimerId) -> Vec<Command> {
+        self.fired.push(id);
+        let mut commands = vec![Command::Redraw];
+        if self.fired.len() == 3 {
+            commands.push(Command::CancelTimer(id));
+        }
+        commands
+    }
+}
+
+#[test]
+fn event_loop_delivers_timers_by_its_clock() {
+    let mut event_loop = EventLoop::new(TestPlatform::default());
+    let mut app = TestApp::default();
+    let mut terminal = Terminal::new(TestBackend::new(40, 13)).unwrap();
+    event_loop.execute(
+        &mut app,
+        vec![Command::StartTimer(
+            RESCAN,
+            Timer::Every(Duration::from_millis(20)),
+        )],
+    );
+
+    // The loop wakes up for the timer, even before the next update
+    event_loop.run_once(&mut app, &mut terminal).unwrap();
+    assert!(app.fired.is_empty());
+    assert_eq!(
+        event_loop.platform_mut().timeouts,
+        [Duration::from_millis(20)]
+    );
+
+    for _ in 0..5 {
+        event_loop
+            .platform_mut()
+            .clock
+            .advance(Duration::from_millis(20));
+        event_loop.run_once(&mut app, &mut terminal).unwrap();
+    }
+    assert_eq!(app.fired, [RESCAN; 3]);
+    assert!(!event_loop.timers().is_running(RESCAN));
+}
@@ -1,5 +0,0 @@
-/// Application setup.
-pub mod setup;
-
-/// Button handling.
-pub mod button;
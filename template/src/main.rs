@@ -1,7 +1,8 @@
 use mousefood::prelude::*;
 use mousefood::ratatui::widgets::{Block, Paragraph};
-use ratatui_mousefood_template::button::Button;
-use ratatui_mousefood_template::setup::App;
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::Button;
+use ratatui_mousefood_runtime::command::Command;
 
 /// Application state.
 ///
@@ -37,9 +38,10 @@ impl App for AppState {
         frame.render_widget(paragraph.block(bordered_block), frame.area());
     }
 
-    /// Handle button press events.
-    fn handle_press(&mut self, button: Button) {
+    /// Handle button press events, and redraw to show the press.
+    fn handle_press(&mut self, button: Button) -> Vec<Command> {
         self.button_pressed = Some(button);
+        vec![Command::Redraw]
     }
 }
 
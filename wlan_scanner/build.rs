@@ -1,5 +1,154 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+/// OUI list in the IEEE CSV format, relative to the package.
+///
+/// Set `OUI_CSV` to the path of the full list from <https://standards-oui.ieee.org/oui/oui.csv>
+/// to use it instead.
+const DEFAULT_OUI_CSV: &str = "data/oui.csv";
+
+/// Vendor names are shortened to this many characters to save flash.
+const MAX_VENDOR_LEN: usize = 20;
+
+/// Legal suffixes that are dropped from vendor names.
+const VENDOR_SUFFIXES: &[&str] = &[
+    "inc",
+    "inc.",
+    "corp",
+    "corp.",
+    "corporation",
+    "corporation.",
+    "co",
+    "co.",
+    "ltd",
+    "ltd.",
+    "llc",
+    "gmbh",
+    "bv",
+    "technologies",
+];
+
 fn main() {
     // Emits Cargo instructions to propagate ESP-IDF system environment
     // variables into the build so the Rust code and the ESP-IDF bindings are aligned.
-    embuild::espidf::sysenv::output();
+    if std::env::var_os("CARGO_FEATURE_ESP").is_some() {
+        embuild::espidf::sysenv::output();
+    }
+
+    generate_oui_table();
+}
+
+/// Turn the OUI CSV into a sorted table in `$OUT_DIR/oui.rs`, for a binary search at runtime.
+///
+/// The table has a 3 byte OUI and a 2 byte vendor index per entry, plus every distinct
+/// vendor name once.
+fn generate_oui_table() {
+    println!("cargo:rerun-if-env-changed=OUI_CSV");
+    let path = env::var("OUI_CSV").unwrap_or_else(|_| DEFAULT_OUI_CSV.to_string());
+    println!("cargo:rerun-if-changed={path}");
+
+    let csv = fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
+
+    // Sorted by OUI, the first entry wins for duplicates
+    let mut entries: BTreeMap<u32, String> = BTreeMap::new();
+    for (i, line) in csv.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let number = i + 1;
+        let fields = split_csv_line(line);
+        let (Some(assignment), Some(name)) = (fields.get(1), fields.get(2)) else {
+            panic!("Malformed line {number} in {path}: {line}");
+        };
+        let assignment = assignment.trim();
+        if assignment.len() != 6 || !assignment.chars().all(|c| c.is_ascii_hexdigit()) {
+            panic!("Bad OUI {assignment:?} on line {number} of {path}, expected 6 hex digits");
+        }
+        let oui = u32::from_str_radix(assignment, 16).unwrap();
+        entries
+            .entry(oui)
+            .or_insert_with(|| short_vendor_name(name));
+    }
+
+    let mut vendors: Vec<String> = entries.values().cloned().collect();
+    vendors.sort();
+    vendors.dedup();
+    assert!(
+        vendors.len() <= usize::from(u16::MAX),
+        "Too many vendors in {path}"
+    );
+
+    let mut out = String::new();
+    writeln!(out, "/// Known OUIs, sorted.").unwrap();
+    writeln!(out, "static OUIS: [[u8; 3]; {}] = [", entries.len()).unwrap();
+    for oui in entries.keys() {
+        let [_, a, b, c] = oui.to_be_bytes();
+        writeln!(out, "    [{a:#04X}, {b:#04X}, {c:#04X}],").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(
+        out,
+        "/// Index into [`VENDORS`] for every entry of [`OUIS`]."
+    )
+    .unwrap();
+    writeln!(out, "static VENDOR_INDEX: [u16; {}] = [", entries.len()).unwrap();
+    for name in entries.values() {
+        let index = vendors.binary_search(name).unwrap();
+        writeln!(out, "    {index},").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out, "/// Distinct vendor names.").unwrap();
+    writeln!(out, "static VENDORS: [&str; {}] = [", vendors.len()).unwrap();
+    for name in &vendors {
+        writeln!(out, "    {name:?},").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("oui.rs"), out).unwrap();
+
+    // A `&str` is a pointer and a length of 4 bytes each on the ESP32
+    let size = entries.len() * (3 + 2)
+        + vendors.len() * 8
+        + vendors.iter().map(String::len).sum::<usize>();
+    println!(
+        "cargo:warning=OUI table: {} entries, {} vendors, {} kB of flash",
+        entries.len(),
+        vendors.len(),
+        size.div_ceil(1024)
+    );
+}
+
+/// Split a CSV line into its fields, removing the quotes around quoted fields.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Shorten a vendor name for the small display, e.g. "Cisco Systems, Inc" to "Cisco Systems".
+fn short_vendor_name(name: &str) -> String {
+    let name = name.split(',').next().unwrap_or_default();
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    while words.len() > 1
+        && VENDOR_SUFFIXES.contains(&words[words.len() - 1].to_lowercase().as_str())
+    {
+        words.pop();
+    }
+    words.join(" ").chars().take(MAX_VENDOR_LEN).collect()
 }
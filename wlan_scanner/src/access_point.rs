@@ -0,0 +1,341 @@
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::RssiHistory;
+use crate::wifi::{AuthMethod, Bssid, Cipher, CountryCode, PhyModes, ScanResult, SecondaryChannel};
+
+/// Default number of scans an access point may be missing from before it is removed.
+pub const DEFAULT_MAX_MISSED_SCANS: u32 = 3;
+
+/// Access point tracked across scans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPoint {
+    /// Network name, empty for hidden networks.
+    pub ssid: String,
+    /// MAC address of the access point.
+    pub bssid: Bssid,
+    /// Primary channel.
+    pub channel: u8,
+    /// Secondary channel of a 40 MHz access point.
+    pub secondary_channel: SecondaryChannel,
+    /// Authentication method.
+    pub auth_method: AuthMethod,
+    /// Cipher suite for unicast traffic.
+    pub pairwise_cipher: Cipher,
+    /// Cipher suite for broadcast traffic.
+    pub group_cipher: Cipher,
+    /// Supported PHY modes.
+    pub phy_modes: PhyModes,
+    /// Advertised country, if any.
+    pub country: Option<CountryCode>,
+    /// Signal strength in dBm from the last scan that found it.
+    pub rssi: i8,
+    /// Weakest signal strength seen.
+    pub rssi_min: i8,
+    /// Strongest signal strength seen.
+    pub rssi_max: i8,
+    /// Sum of all signal strengths, for the average.
+    rssi_sum: i64,
+    /// Most recent signal strengths.
+    pub history: RssiHistory,
+    /// Number of scans that found it.
+    pub seen_count: u32,
+    /// Time of the first scan that found it.
+    pub first_seen: Instant,
+    /// Time of the last scan that found it.
+    pub last_seen: Instant,
+    /// Number of the first scan that found it.
+    pub first_scan: u32,
+    /// Number of the last scan that found it.
+    pub last_scan: u32,
+}
+
+impl AccessPoint {
+    /// Start tracking an access point found by the given scan.
+    fn new(result: ScanResult, scan: u32, now: Instant) -> Self {
+        let mut history = RssiHistory::default();
+        history.push(result.rssi);
+        Self {
+            ssid: result.ssid,
+            bssid: result.bssid,
+            channel: result.channel,
+            secondary_channel: result.secondary_channel,
+            auth_method: result.auth_method,
+            pairwise_cipher: result.pairwise_cipher,
+            group_cipher: result.group_cipher,
+            phy_modes: result.phy_modes,
+            country: result.country,
+            rssi: result.rssi,
+            rssi_min: result.rssi,
+            rssi_max: result.rssi,
+            rssi_sum: result.rssi.into(),
+            history,
+            seen_count: 1,
+            first_seen: now,
+            last_seen: now,
+            first_scan: scan,
+            last_scan: scan,
+        }
+    }
+
+    /// Update the access point with a newer scan result.
+    fn update(&mut self, result: ScanResult, scan: u32, now: Instant) {
+        self.ssid = result.ssid;
+        self.channel = result.channel;
+        self.secondary_channel = result.secondary_channel;
+        self.auth_method = result.auth_method;
+        self.pairwise_cipher = result.pairwise_cipher;
+        self.group_cipher = result.group_cipher;
+        self.phy_modes = result.phy_modes;
+        self.country = result.country;
+        self.rssi = result.rssi;
+        self.rssi_min = self.rssi_min.min(result.rssi);
+        self.rssi_max = self.rssi_max.max(result.rssi);
+        self.rssi_sum += i64::from(result.rssi);
+        self.history.push(result.rssi);
+        self.seen_count += 1;
+        self.last_seen = now;
+        self.last_scan = scan;
+    }
+
+    /// Average signal strength in dBm over all scans that found it.
+    pub fn rssi_average(&self) -> i8 {
+        (self.rssi_sum / i64::from(self.seen_count)) as i8
+    }
+}
+
+/// Order of the access point list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortKey {
+    /// Strongest signal first.
+    #[default]
+    Rssi,
+    /// By network name, hidden networks last.
+    Ssid,
+    /// By channel, lowest first.
+    Channel,
+    /// By authentication method, open networks first.
+    Security,
+}
+
+impl SortKey {
+    /// The sort key after this one, wrapping around at the end.
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Rssi => SortKey::Ssid,
+            SortKey::Ssid => SortKey::Channel,
+            SortKey::Channel => SortKey::Security,
+            SortKey::Security => SortKey::Rssi,
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortKey::Rssi => write!(f, "RSSI"),
+            SortKey::Ssid => write!(f, "SSID"),
+            SortKey::Channel => write!(f, "Channel"),
+            SortKey::Security => write!(f, "Security"),
+        }
+    }
+}
+
+/// Selection of the access points to list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// All access points.
+    #[default]
+    All,
+    /// Access points with a visible network name.
+    HideHidden,
+    /// Access points without authentication.
+    OpenOnly,
+    /// Access points on a single channel.
+    Channel(u8),
+}
+
+impl Filter {
+    /// Check if an access point passes the filter.
+    pub fn matches(&self, access_point: &AccessPoint) -> bool {
+        match *self {
+            Filter::All => true,
+            Filter::HideHidden => !access_point.ssid.is_empty(),
+            Filter::OpenOnly => access_point.auth_method == AuthMethod::Open,
+            Filter::Channel(channel) => access_point.channel == channel,
+        }
+    }
+
+    /// The filter after this one, wrapping around at the end.
+    ///
+    /// Only the channels that are used by a tracked access point are offered.
+    pub fn next(self, access_points: &AccessPointStore) -> Self {
+        let channels: BTreeSet<u8> = access_points
+            .iter()
+            .map(|access_point| access_point.channel)
+            .collect();
+        let next_channel = |after: u8| {
+            channels
+                .range(after.saturating_add(1)..)
+                .next()
+                .map_or(Filter::All, |&channel| Filter::Channel(channel))
+        };
+
+        match self {
+            Filter::All => Filter::HideHidden,
+            Filter::HideHidden => Filter::OpenOnly,
+            Filter::OpenOnly => next_channel(0),
+            Filter::Channel(channel) => next_channel(channel),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::All => write!(f, "All"),
+            Filter::HideHidden => write!(f, "No hidden"),
+            Filter::OpenOnly => write!(f, "Open"),
+            Filter::Channel(channel) => write!(f, "Ch {channel}"),
+        }
+    }
+}
+
+/// Access points of successive scans, merged by BSSID.
+#[derive(Debug)]
+pub struct AccessPointStore {
+    access_points: BTreeMap<Bssid, AccessPoint>,
+    /// Number of scans merged so far.
+    scan_count: u32,
+    /// Number of scans an access point may be missing from before it is removed.
+    max_missed_scans: u32,
+    /// Access points that were first found by the last scan.
+    appeared: Vec<Bssid>,
+    /// Access points that were removed after the last scan.
+    vanished: Vec<AccessPoint>,
+}
+
+impl Default for AccessPointStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_MISSED_SCANS)
+    }
+}
+
+impl AccessPointStore {
+    /// Create an empty store that removes access points missing from `max_missed_scans` scans.
+    pub fn new(max_missed_scans: u32) -> Self {
+        Self {
+            access_points: BTreeMap::new(),
+            scan_count: 0,
+            max_missed_scans,
+            appeared: Vec::new(),
+            vanished: Vec::new(),
+        }
+    }
+
+    /// Merge the results of a new scan, done at `now`.
+    pub fn merge(&mut self, results: Vec<ScanResult>, now: Instant) {
+        self.scan_count += 1;
+        let scan = self.scan_count;
+        self.appeared.clear();
+
+        for result in results {
+            match self.access_points.get_mut(&result.bssid) {
+                Some(access_point) => access_point.update(result, scan, now),
+                None => {
+                    self.appeared.push(result.bssid);
+                    self.access_points
+                        .insert(result.bssid, AccessPoint::new(result, scan, now));
+                }
+            }
+        }
+
+        let vanished: Vec<Bssid> = self
+            .iter()
+            .filter(|access_point| scan - access_point.last_scan >= self.max_missed_scans)
+            .map(|access_point| access_point.bssid)
+            .collect();
+        self.vanished = vanished
+            .iter()
+            .filter_map(|bssid| self.access_points.remove(bssid))
+            .collect();
+    }
+
+    /// Get an access point by its BSSID.
+    pub fn get(&self, bssid: &Bssid) -> Option<&AccessPoint> {
+        self.access_points.get(bssid)
+    }
+
+    /// Iterate over the tracked access points, ordered by BSSID.
+    pub fn iter(&self) -> impl Iterator<Item = &AccessPoint> {
+        self.access_points.values()
+    }
+
+    /// Tracked access points, strongest signal first.
+    pub fn by_rssi(&self) -> Vec<&AccessPoint> {
+        let mut access_points: Vec<_> = self.iter().collect();
+        access_points.sort_by_key(|access_point| std::cmp::Reverse(access_point.rssi));
+        access_points
+    }
+
+    /// Tracked access points that pass `filter`, ordered by `sort`.
+    ///
+    /// Access points that compare equal are ordered by signal strength, strongest first.
+    pub fn list(&self, sort: SortKey, filter: Filter) -> Vec<&AccessPoint> {
+        let mut access_points: Vec<_> = self
+            .by_rssi()
+            .into_iter()
+            .filter(|access_point| filter.matches(access_point))
+            .collect();
+        match sort {
+            SortKey::Rssi => {}
+            SortKey::Ssid => access_points.sort_by_key(|access_point| {
+                (
+                    access_point.ssid.is_empty(),
+                    access_point.ssid.to_lowercase(),
+                )
+            }),
+            SortKey::Channel => access_points.sort_by_key(|access_point| access_point.channel),
+            SortKey::Security => access_points.sort_by_key(|access_point| access_point.auth_method),
+        }
+        access_points
+    }
+
+    /// Number of tracked access points.
+    pub fn len(&self) -> usize {
+        self.access_points.len()
+    }
+
+    /// Check if no access points are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.access_points.is_empty()
+    }
+
+    /// Number of scans merged so far.
+    pub fn scan_count(&self) -> u32 {
+        self.scan_count
+    }
+
+    /// Check if an access point was first found by the last scan.
+    pub fn is_new(&self, access_point: &AccessPoint) -> bool {
+        access_point.first_scan == self.scan_count
+    }
+
+    /// Check if an access point was missing from the last scan, but is not removed yet.
+    pub fn is_missing(&self, access_point: &AccessPoint) -> bool {
+        access_point.last_scan != self.scan_count
+    }
+
+    /// Access points that were first found by the last scan.
+    pub fn appeared(&self) -> impl Iterator<Item = &AccessPoint> {
+        self.appeared.iter().filter_map(|bssid| self.get(bssid))
+    }
+
+    /// Access points that were removed after the last scan.
+    pub fn vanished(&self) -> &[AccessPoint] {
+        &self.vanished
+    }
+}
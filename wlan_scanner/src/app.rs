@@ -0,0 +1,1098 @@
+use std::io::{self, Write};
+use std::mem;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::Thread;
+use std::time::{Duration, Instant};
+
+use crate::access_point::{AccessPoint, AccessPointStore, Filter};
+use crate::channel::channel_usage;
+use crate::console::{Command, HELP, LogFormat, spawn_console};
+use crate::fox_hunt::{FoxHunt, Trend};
+use crate::history::RssiHistory;
+use crate::scan_log::ScanLog;
+use crate::settings::{Setting, Settings};
+use crate::vendor::vendor_for;
+use crate::wifi::{Bssid, ScanError, ScanResult, ScanState, WifiScanner, spawn_scanner};
+use mousefood::prelude::*;
+use mousefood::ratatui::widgets::{
+    Bar, BarChart, BarGroup, Block, BorderType, Borders, Gauge, List, ListItem, ListState,
+    Paragraph, Sparkline, Tabs,
+};
+use ratatui_mousefood_runtime::app::{App, Context};
+use ratatui_mousefood_runtime::backlight::BacklightConfig;
+use ratatui_mousefood_runtime::button::{
+    Button, ButtonConfig, ButtonEvent, ButtonId, ButtonPressType, RepeatConfig,
+};
+use ratatui_mousefood_runtime::clock::SystemClock;
+use ratatui_mousefood_runtime::command::Command as RuntimeCommand;
+use ratatui_mousefood_runtime::hold_indicator::HoldIndicator;
+use ratatui_mousefood_runtime::storage::{Namespace, StorageError};
+
+/// Number of missed scans after which a hunted access point is shown as lost.
+const MISSED_SCANS_LOST: u32 = 3;
+
+/// The backlight is turned off after this many times the idle timeout of the settings.
+const BACKLIGHT_OFF_FACTOR: u32 = 4;
+
+/// Height of the network list with its borders, below which the info panel is left out.
+const MIN_LIST_HEIGHT: u16 = 5;
+
+/// Height of the info panel on the Main tab, with its borders.
+const INFO_HEIGHT: u16 = 7;
+
+/// Application state with multi-tab navigation.
+#[derive(Default)]
+pub struct AppState {
+    /// Currently selected tab (0 = Main, 1 = Stats, 2 = Channels, 3 = Settings)
+    selected_tab: usize,
+    /// Access point selected on the Main tab (the first listed one if not set)
+    selected: Option<Bssid>,
+    /// User settings, including the order of the network list
+    settings: Settings,
+    /// Storage namespace the settings are saved to after every change
+    settings_namespace: Option<Namespace>,
+    /// Setting selected on the Settings tab
+    selected_setting: Setting,
+    /// Networks shown in the list
+    filter: Filter,
+    /// Whether the detail screen of the selected access point is open
+    detail_open: bool,
+    /// Scan results, filled in by the scan thread
+    scan: Arc<Mutex<ScanState>>,
+    /// Scan thread, unparked to scan right away
+    scan_thread: Option<Thread>,
+    /// Commands read by the console thread
+    console: Option<Receiver<Command>>,
+    /// Status message shown in footer
+    status_message: String,
+    /// Tracks the last button that was pressed
+    last_button: Option<Button>,
+    /// Bar in the footer that fills while a button is held
+    hold_indicator: HoldIndicator,
+    /// Commands for the event loop, returned after the next press or update
+    pending_commands: Vec<RuntimeCommand>,
+    stats_tab_counter: u16,
+}
+
+impl AppState {
+    /// Create a new app state with default values
+    pub fn new() -> Self {
+        Self {
+            selected_tab: 0,
+            selected: None,
+            settings: Settings::default(),
+            settings_namespace: None,
+            selected_setting: Setting::default(),
+            filter: Filter::default(),
+            detail_open: false,
+            scan: Arc::default(),
+            scan_thread: None,
+            console: None,
+            status_message: "Ready".to_string(),
+            last_button: None,
+            hold_indicator: HoldIndicator::default(),
+            pending_commands: Vec::new(),
+            stats_tab_counter: 0,
+        }
+    }
+
+    /// Scan in the background with the scanner created by `make_scanner`
+    ///
+    /// The scans are timed by the [`SystemClock`], like the event loop on the T-Display and in
+    /// the simulator.
+    pub fn with_scanner<S, F>(mut self, make_scanner: F) -> Self
+    where
+        S: WifiScanner,
+        F: FnOnce() -> Result<S, ScanError> + Send + 'static,
+    {
+        let handle = spawn_scanner(make_scanner, Arc::clone(&self.scan), SystemClock);
+        self.scan_thread = Some(handle.thread().clone());
+        self
+    }
+
+    /// Set the time between two background scans, without saving it
+    pub fn with_scan_interval(mut self, interval: Duration) -> Self {
+        self.settings.scan_interval = interval;
+        self.apply_settings();
+        self
+    }
+
+    /// Load the settings from `namespace`, and save them there after every change
+    ///
+    /// If they cannot be loaded, the default settings are used and the error is shown. Settings
+    /// of a newer version are kept, and changes are not saved over them.
+    pub fn with_settings(mut self, namespace: Namespace) -> Self {
+        match Settings::load(&namespace) {
+            Ok(settings) => self.settings = settings,
+            Err(e) => self.status_message = e.to_string(),
+        }
+        self.settings_namespace = Some(namespace);
+        self.apply_settings();
+        self
+    }
+
+    /// Get the current settings
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Append every successful scan to a persistent log
+    pub fn with_scan_log(self, log: ScanLog) -> Self {
+        self.scan.lock().unwrap().log = Some(log);
+        self
+    }
+
+    /// Read commands from `stdin` and write their output to `stdout`, e.g. from the serial port
+    pub fn with_console(mut self) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        spawn_console(io::BufReader::new(io::stdin()), io::stdout(), sender);
+        self.console = Some(receiver);
+        self
+    }
+
+    /// Run a console command and write its output
+    pub fn run_command(&mut self, command: Command, out: &mut impl Write) -> io::Result<()> {
+        match command {
+            Command::Scan => {
+                if self.scan_thread.is_none() {
+                    return writeln!(out, "error: no scanner");
+                }
+                self.start_scan();
+            }
+            Command::List => {
+                let scan = self.scan.lock().unwrap();
+                for (i, access_point) in self
+                    .listed_access_points(&scan.access_points)
+                    .iter()
+                    .enumerate()
+                {
+                    writeln!(
+                        out,
+                        "{:2}. {} {:>4} {:>2} {:<6} {}",
+                        i + 1,
+                        access_point.bssid,
+                        access_point.rssi,
+                        access_point.channel,
+                        access_point.auth_method.to_string(),
+                        access_point.ssid,
+                    )?;
+                }
+                return Ok(());
+            }
+            Command::Sort(sort) => {
+                self.settings.sort = sort;
+                self.status_message = format!("Sorted by {}", self.settings.sort);
+                self.save_settings();
+            }
+            Command::Filter(filter) => {
+                self.filter = filter;
+                self.status_message = format!("Filter: {}", self.filter);
+            }
+            Command::Tab(tab) => {
+                self.selected_tab = tab;
+                self.status_message = format!("Switched to tab {}", self.selected_tab + 1);
+            }
+            Command::Press(button) => {
+                let commands = self.handle_press(button);
+                self.pending_commands.extend(commands);
+            }
+            Command::DumpLog(format) => {
+                let save = {
+                    let mut scan = self.scan.lock().unwrap();
+                    let Some(log) = &mut scan.log else {
+                        return writeln!(out, "error: no scan log");
+                    };
+                    match format {
+                        LogFormat::Csv => log.write_csv(out)?,
+                        LogFormat::Json => log.write_json(out)?,
+                    }
+                    log.stage()
+                };
+                // Save the dumped entries, after releasing the lock
+                if let Err(e) = save.and_then(|namespace| namespace.commit()) {
+                    self.status_message = e.to_string();
+                }
+                return Ok(());
+            }
+            Command::ClearLog => {
+                let save = {
+                    let mut scan = self.scan.lock().unwrap();
+                    let Some(log) = &mut scan.log else {
+                        return writeln!(out, "error: no scan log");
+                    };
+                    log.clear()
+                };
+                if let Err(e) = save.and_then(|namespace| namespace.commit()) {
+                    return writeln!(out, "error: {e}");
+                }
+            }
+            Command::SetInterval(interval) => {
+                self.settings.scan_interval = interval;
+                self.status_message = format!("Scan interval {}s", interval.as_secs());
+                self.apply_settings();
+                self.save_settings();
+                self.start_scan();
+            }
+            Command::Help => return writeln!(out, "{HELP}"),
+        }
+        writeln!(out, "ok")
+    }
+
+    /// Store the outcome of a scan that finished at `now`, as the scan thread does
+    ///
+    /// During a signal hunt the result is taken as a scan of the hunted channel. The scan log is
+    /// saved by the event loop once it is due, with [`RuntimeCommand::Persist`].
+    pub fn apply_scan(&mut self, result: Result<Vec<ScanResult>, ScanError>, now: Instant) {
+        let mut scan = self.scan.lock().unwrap();
+        if scan.hunt.is_some() {
+            scan.apply_hunt(result);
+        } else if let Some(namespace) = scan.apply(result, now) {
+            self.pending_commands
+                .push(RuntimeCommand::Persist(namespace));
+        }
+    }
+
+    /// Get the listed access points, with the current sort and filter
+    fn listed_access_points<'a>(
+        &self,
+        access_points: &'a AccessPointStore,
+    ) -> Vec<&'a AccessPoint> {
+        access_points.list(self.settings.sort, self.filter)
+    }
+
+    /// Get the index of the selected access point in the list, or the first one if none is selected
+    fn selected_index(&self, listed: &[&AccessPoint]) -> Option<usize> {
+        self.selected
+            .and_then(|bssid| {
+                listed
+                    .iter()
+                    .position(|access_point| access_point.bssid == bssid)
+            })
+            .or_else(|| (!listed.is_empty()).then_some(0))
+    }
+
+    /// Get the selected access point, or the first listed one if none is selected
+    fn selected_access_point<'a>(
+        &self,
+        access_points: &'a AccessPointStore,
+    ) -> Option<&'a AccessPoint> {
+        let listed = self.listed_access_points(access_points);
+        self.selected_index(&listed).map(|i| listed[i])
+    }
+
+    /// Select the next access point in the list, wrapping around at the end
+    fn select_next(&mut self) {
+        let scan = self.scan.lock().unwrap();
+        let access_points = self.listed_access_points(&scan.access_points);
+        let next = self
+            .selected_index(&access_points)
+            .map_or(0, |i| (i + 1) % access_points.len());
+
+        if let Some(access_point) = access_points.get(next) {
+            self.selected = Some(access_point.bssid);
+            self.status_message = format!("Selected {}", display_ssid(access_point));
+        }
+    }
+
+    /// Sort the network list by the next key
+    fn next_sort(&mut self) {
+        self.settings.sort = self.settings.sort.next();
+        self.status_message = format!("Sorted by {}", self.settings.sort);
+        self.save_settings();
+    }
+
+    /// Filter the network list with the next filter
+    fn next_filter(&mut self) {
+        self.filter = self.filter.next(&self.scan.lock().unwrap().access_points);
+        self.status_message = format!("Filter: {}", self.filter);
+    }
+
+    /// Open the detail screen of the selected access point
+    fn open_detail(&mut self) {
+        let scan = self.scan.lock().unwrap();
+        if let Some(access_point) = self.selected_access_point(&scan.access_points) {
+            self.selected = Some(access_point.bssid);
+            self.status_message = format!("{} details", display_ssid(access_point));
+            self.detail_open = true;
+        }
+    }
+
+    /// Close the detail screen and return to the list
+    fn close_detail(&mut self) {
+        self.detail_open = false;
+        self.status_message = "Back to list".to_string();
+    }
+
+    /// Check if a signal hunt is in progress
+    fn is_hunting(&self) -> bool {
+        self.scan.lock().unwrap().hunt.is_some()
+    }
+
+    /// Start a signal hunt for the selected access point
+    fn start_hunt(&mut self) {
+        let mut scan = self.scan.lock().unwrap();
+        let Some(access_point) = self.selected_access_point(&scan.access_points) else {
+            return;
+        };
+        self.status_message = format!("Hunting {}", display_ssid(access_point));
+        scan.hunt = Some(FoxHunt::new(access_point));
+        drop(scan);
+        self.start_scan();
+    }
+
+    /// Stop the signal hunt and go back to the detail screen
+    fn stop_hunt(&mut self) {
+        self.scan.lock().unwrap().hunt = None;
+        self.status_message = "Hunt stopped".to_string();
+        self.start_scan();
+    }
+
+    /// Let the scan thread scan right away, instead of waiting for the scan interval
+    fn start_scan(&mut self) {
+        if let Some(thread) = &self.scan_thread {
+            self.pending_commands
+                .push(RuntimeCommand::Unpark(thread.clone()));
+        }
+    }
+
+    /// Take the commands for the event loop, and redraw if the app changed
+    fn take_commands(&mut self, changed: bool) -> Vec<RuntimeCommand> {
+        let mut commands = mem::take(&mut self.pending_commands);
+        if changed {
+            commands.push(RuntimeCommand::Redraw);
+        }
+        commands
+    }
+
+    /// Select the next setting on the Settings tab
+    fn next_setting(&mut self) {
+        self.selected_setting = self.selected_setting.next();
+        self.status_message = self.selected_setting.to_string();
+    }
+
+    /// Pass the settings on to the parts of the app that use them
+    fn apply_settings(&mut self) {
+        self.scan.lock().unwrap().interval = self.settings.scan_interval;
+    }
+
+    /// Save the settings, if they are kept in a storage
+    fn save_settings(&mut self) {
+        let Some(namespace) = &self.settings_namespace else {
+            return;
+        };
+        if let Err(e) = self.settings.save(namespace) {
+            self.status_message = e.to_string();
+        }
+    }
+
+    /// Move to the next tab
+    fn next_tab(&mut self) {
+        self.selected_tab = (self.selected_tab + 1) % 4;
+        self.status_message = format!("Switched to tab {}", self.selected_tab + 1);
+    }
+
+    /// Perform action based on current tab
+    fn perform_action(&mut self) {
+        match self.selected_tab {
+            0 => {
+                // Main tab: select the next network
+                self.select_next();
+            }
+            1 => {
+                // Stats tab: refresh data
+                self.status_message = "Stats refreshed!".to_string();
+                self.stats_tab_counter = (self.stats_tab_counter + 5) % 100;
+            }
+            3 => {
+                // Settings tab: change the selected setting
+                self.settings.next_value(self.selected_setting);
+                self.status_message = format!(
+                    "{}: {}",
+                    self.selected_setting,
+                    self.settings.value(self.selected_setting)
+                );
+                self.apply_settings();
+                self.save_settings();
+            }
+            _ => {}
+        }
+    }
+
+    /// Act on a button press
+    fn press(&mut self, button: Button) {
+        self.last_button = Some(button);
+
+        if button == Button::Both(ButtonPressType::Hold) {
+            // Turn the display off until the next press
+            self.pending_commands.push(RuntimeCommand::Sleep);
+            return;
+        }
+
+        if self.is_hunting() {
+            if button == Button::Button1(ButtonPressType::Short) {
+                self.stop_hunt();
+            }
+            return;
+        }
+
+        if self.detail_open {
+            match button {
+                Button::Button1(ButtonPressType::Short) => self.close_detail(),
+                Button::Button2(ButtonPressType::Short) => self.start_hunt(),
+                _ => {}
+            }
+            return;
+        }
+
+        match button {
+            Button::Button1(ButtonPressType::Short) => self.next_tab(),
+            Button::Button2(ButtonPressType::Short) => self.perform_action(),
+            Button::Button2(ButtonPressType::Repeat) if self.selected_tab == 0 => {
+                self.select_next()
+            }
+            Button::Button1(ButtonPressType::Long) if self.selected_tab == 0 => self.next_filter(),
+            Button::Button2(ButtonPressType::Long) if self.selected_tab == 0 => self.open_detail(),
+            Button::Button1(ButtonPressType::Long) if self.selected_tab == 3 => self.next_setting(),
+            Button::Both(ButtonPressType::Short) if self.selected_tab == 0 => self.next_sort(),
+            _ => {}
+        }
+    }
+}
+
+/// The main application trait implementation.
+impl App for AppState {
+    /// Draw the UI frame.
+    fn draw(&self, frame: &mut Frame) {
+        // Create main layout: header, content, footer
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header with tabs
+                Constraint::Min(0),    // Main content area
+                Constraint::Length(3), // Footer with status
+            ])
+            .split(frame.area());
+
+        // Render header with tabs
+        self.draw_header(frame, chunks[0]);
+
+        // Render content based on selected tab
+        match self.selected_tab {
+            0 if self.is_hunting() => self.draw_hunt(frame, chunks[1]),
+            0 if self.detail_open => self.draw_detail(frame, chunks[1]),
+            0 => self.draw_main_tab(frame, chunks[1]),
+            1 => self.draw_stats_tab(frame, chunks[1]),
+            2 => self.draw_channels_tab(frame, chunks[1]),
+            3 => self.draw_settings_tab(frame, chunks[1]),
+            _ => {}
+        }
+
+        // Render footer
+        self.draw_footer(frame, chunks[2]);
+    }
+
+    /// Handle button press events, and redraw after every press.
+    fn handle_press(&mut self, button: Button) -> Vec<RuntimeCommand> {
+        self.press(button);
+        self.take_commands(true)
+    }
+
+    /// Show how long a button is held in the footer.
+    fn handle_event(&mut self, event: ButtonEvent) -> Vec<RuntimeCommand> {
+        let config = self.button_config(event.button());
+        let changed = self.hold_indicator.handle_event(event, &config);
+        self.take_commands(changed)
+    }
+
+    /// Show that the scan log could not be saved.
+    fn on_persist_error(
+        &mut self,
+        _namespace: &Namespace,
+        error: StorageError,
+    ) -> Vec<RuntimeCommand> {
+        self.status_message = format!("Save failed: {error}");
+        self.take_commands(true)
+    }
+
+    /// Use the brightness and idle timeout from the settings.
+    ///
+    /// The backlight stays on during a signal hunt, since the buttons are not used while
+    /// walking around.
+    fn backlight(&self) -> BacklightConfig {
+        let dim_after = self.settings.idle_timeout.filter(|_| !self.is_hunting());
+        BacklightConfig {
+            brightness: self.settings.brightness,
+            dim_after,
+            off_after: dim_after.map(|dim_after| dim_after * BACKLIGHT_OFF_FACTOR),
+            ..BacklightConfig::default()
+        }
+    }
+
+    /// Use the long-press threshold from the settings for both buttons.
+    ///
+    /// Button 2 repeats in the network list once it is held for twice as long, to scroll
+    /// through it.
+    fn button_config(&self, button: ButtonId) -> ButtonConfig {
+        let scrolls = button == ButtonId::Button2
+            && self.selected_tab == 0
+            && !self.detail_open
+            && !self.is_hunting();
+        ButtonConfig {
+            long_press: self.settings.long_press,
+            repeat: scrolls.then(|| RepeatConfig {
+                delay: self.settings.long_press * 2,
+                ..RepeatConfig::default()
+            }),
+            ..ButtonConfig::default()
+        }
+    }
+
+    /// Run the commands from the console, and redraw after them and after every scan.
+    fn update(&mut self, _ctx: &Context, _dt: Duration) -> Vec<RuntimeCommand> {
+        let mut changed = self.scan.lock().unwrap().take_changed();
+        if let Some(console) = self.console.take() {
+            let mut out = io::stdout().lock();
+            for command in console.try_iter() {
+                let _ = self
+                    .run_command(command, &mut out)
+                    .and_then(|()| out.flush());
+                changed = true;
+            }
+            self.console = Some(console);
+        }
+        self.take_commands(changed)
+    }
+}
+
+impl AppState {
+    /// Draw the header with tab navigation
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let titles = vec!["Main", "Stats", "Chan", "Settings"];
+        let tabs = Tabs::new(titles)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" 🐀 ESP32 Workshop ")
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::new().fg(self.settings.theme.accent())),
+            )
+            .select(self.selected_tab)
+            .style(Style::default().white())
+            .highlight_style(Style::default().fg(self.settings.theme.accent()).bold());
+
+        frame.render_widget(tabs, area);
+    }
+
+    /// Draw the main tab with a list view
+    fn draw_main_tab(&self, frame: &mut Frame, area: Rect) {
+        // The info panel is only shown if the list keeps room for a few networks, which it does
+        // not on the display of the T-Display, but the sort and filter line always is
+        let info_height = if area.height >= MIN_LIST_HEIGHT + 1 + INFO_HEIGHT {
+            INFO_HEIGHT
+        } else {
+            0
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(info_height),
+            ])
+            .split(area);
+
+        let scan = self.scan.lock().unwrap();
+
+        // Network list
+        let access_points = &scan.access_points;
+        let listed = self.listed_access_points(access_points);
+        let selected_index = self.selected_index(&listed);
+        let mut items: Vec<ListItem> = listed
+            .iter()
+            .enumerate()
+            .map(|(i, access_point)| {
+                let marker = if access_points.is_new(access_point) {
+                    "+".green()
+                } else if access_points.is_missing(access_point) {
+                    "-".red()
+                } else {
+                    " ".into()
+                };
+                let ssid = if access_point.ssid.is_empty() {
+                    Span::styled(format!("{:<11}", "<hidden>"), Style::default().dark_gray())
+                } else {
+                    Span::raw(format!("{:<11.11}", access_point.ssid))
+                };
+                let content = Line::from(vec![
+                    Span::styled(format!("{:2}.", i + 1), Style::default().dark_gray()),
+                    marker,
+                    ssid,
+                    Span::styled(
+                        format!(" {:>4}", access_point.rssi),
+                        rssi_style(access_point.rssi),
+                    ),
+                    Span::styled(
+                        format!(" {:>2} ", access_point.channel),
+                        Style::default().cyan(),
+                    ),
+                    Span::styled(
+                        format!("{:<6}", access_point.auth_method.to_string()),
+                        Style::default().dark_gray(),
+                    ),
+                    Span::styled(
+                        format!(" {:.7}", vendor(access_point)),
+                        Style::default().dark_gray(),
+                    ),
+                ]);
+                ListItem::new(content)
+            })
+            .collect();
+
+        if items.is_empty() {
+            let message = match &scan.last_error {
+                Some(e) => e.to_string().red(),
+                None if !access_points.is_empty() => "No matching networks".dark_gray(),
+                None => "Scanning...".dark_gray(),
+            };
+            items.push(ListItem::new(Line::from(message)));
+        }
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" Networks ")
+                    .title_bottom(Line::from(" Hold BTN2 for details ".dark_gray()))
+                    .border_style(Style::new().cyan()),
+            )
+            .style(Style::default().white())
+            .highlight_style(Style::default().reversed());
+
+        let mut list_state = ListState::default().with_selected(selected_index);
+        frame.render_stateful_widget(list, chunks[0], &mut list_state);
+
+        // Active sort and filter
+        let sort_filter = Line::from(vec![
+            " Sort: ".dark_gray(),
+            self.settings.sort.to_string().cyan(),
+            "  Filter: ".dark_gray(),
+            self.filter.to_string().cyan(),
+        ]);
+        frame.render_widget(sort_filter, chunks[1]);
+
+        if info_height == 0 {
+            return;
+        }
+
+        // Info panel
+        let info_text = vec![
+            Line::from(vec![
+                "Networks: ".dark_gray(),
+                access_points.len().to_string().cyan(),
+                "  Scans: ".dark_gray(),
+                access_points.scan_count().to_string().cyan(),
+            ]),
+            Line::from(vec![
+                "New: ".dark_gray(),
+                access_points.appeared().count().to_string().green(),
+                "  Gone: ".dark_gray(),
+                access_points.vanished().len().to_string().red(),
+            ]),
+            Line::from(""),
+            Line::from("BTN2 select, hold for details".dark_gray()),
+            Line::from("Hold BTN1 to filter, both to sort".dark_gray()),
+        ];
+
+        let info = Paragraph::new(info_text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(" Info ")
+                .border_style(Style::new().cyan()),
+        );
+
+        frame.render_widget(info, chunks[2]);
+    }
+
+    /// Draw the detail screen of the selected access point
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let scan = self.scan.lock().unwrap();
+        let Some(access_point) = self.selected_access_point(&scan.access_points) else {
+            let message = Paragraph::new("Network is gone".dark_gray()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" Details ")
+                    .border_style(Style::new().cyan()),
+            );
+            frame.render_widget(message, area);
+            return;
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", display_ssid(access_point)))
+            .title_bottom(Line::from(" BTN1 close, BTN2 hunt ".dark_gray()))
+            .border_style(Style::new().cyan());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let channel = match access_point.secondary_channel.channel(access_point.channel) {
+            Some(secondary) => format!("{}+{}", access_point.channel, secondary),
+            None => access_point.channel.to_string(),
+        };
+        let country = access_point
+            .country
+            .map_or("-".to_string(), |country| country.to_string());
+        let history = &access_point.history;
+
+        let text = vec![
+            Line::from(vec![
+                access_point.bssid.to_string().cyan(),
+                " ".into(),
+                vendor(access_point).dark_gray(),
+            ]),
+            Line::from(vec![
+                "Ch ".dark_gray(),
+                channel.cyan(),
+                "  PHY ".dark_gray(),
+                access_point.phy_modes.to_string().into(),
+                "  CC ".dark_gray(),
+                country.into(),
+            ]),
+            Line::from(vec![
+                access_point.auth_method.to_string().into(),
+                "  ".into(),
+                access_point.pairwise_cipher.to_string().into(),
+                "/".dark_gray(),
+                access_point.group_cipher.to_string().into(),
+            ]),
+            Line::from(vec![
+                "RSSI ".dark_gray(),
+                Span::styled(access_point.rssi.to_string(), rssi_style(access_point.rssi)),
+                " min ".dark_gray(),
+                format_rssi(history.min()).into(),
+                " max ".dark_gray(),
+                format_rssi(history.max()).into(),
+            ]),
+        ];
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(text.len() as u16), Constraint::Min(0)])
+            .split(inner);
+
+        frame.render_widget(Paragraph::new(text), chunks[0]);
+
+        let data = sparkline_data(history);
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .style(rssi_style(access_point.rssi));
+
+        frame.render_widget(sparkline, chunks[1]);
+    }
+
+    /// Draw the signal hunt with a large gauge of the smoothed signal strength
+    fn draw_hunt(&self, frame: &mut Frame, area: Rect) {
+        let scan = self.scan.lock().unwrap();
+        let Some(hunt) = &scan.hunt else {
+            return;
+        };
+
+        let ssid = if hunt.ssid.is_empty() {
+            "<hidden>"
+        } else {
+            &hunt.ssid
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" Hunting {} ch {} ", ssid, hunt.channel))
+            .title_bottom(Line::from(" BTN1 to stop ".dark_gray()))
+            .border_style(Style::new().red());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(2)])
+            .split(inner);
+
+        // Smoothed signal strength, from -100 dBm (empty) to -30 dBm (full)
+        let rssi = hunt.smoothed_rssi().unwrap_or(-100);
+        let percent = ((i16::from(rssi) + 100) * 100 / 70).clamp(0, 100) as u16;
+        let arrow = match hunt.trend() {
+            Trend::Rising => "↑",
+            Trend::Falling => "↓",
+            Trend::Steady => "→",
+        };
+        let label = if hunt.missed_scans >= MISSED_SCANS_LOST {
+            format!("lost, last {rssi} dBm")
+        } else {
+            format!("{rssi} dBm {arrow}")
+        };
+        let gauge = Gauge::default()
+            .gauge_style(rssi_style(rssi))
+            .percent(percent)
+            .label(label.bold());
+
+        frame.render_widget(gauge, chunks[0]);
+
+        // Raw samples of the fast scans
+        let data = sparkline_data(&hunt.history);
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .style(Style::default().yellow());
+
+        frame.render_widget(sparkline, chunks[1]);
+    }
+
+    /// Draw the stats tab with charts and gauges
+    fn draw_stats_tab(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(10),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        // Gauge example
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" Progress ")
+                    .border_style(Style::new().green()),
+            )
+            .gauge_style(Style::default().green())
+            .percent(self.stats_tab_counter);
+
+        frame.render_widget(gauge, chunks[0]);
+
+        // Signal strength history of the selected network
+        let scan = self.scan.lock().unwrap();
+        let selected = self.selected_access_point(&scan.access_points);
+        let (title, labels) = match selected {
+            Some(access_point) => (
+                format!(" {} ", display_ssid(access_point)),
+                Line::from(vec![
+                    " now ".dark_gray(),
+                    format_rssi(access_point.history.latest()).into(),
+                    " min ".dark_gray(),
+                    format_rssi(access_point.history.min()).into(),
+                    " max ".dark_gray(),
+                    format_rssi(access_point.history.max()).into(),
+                    " ".into(),
+                ]),
+            ),
+            None => (" RSSI ".to_string(), Line::from(" no network ".dark_gray())),
+        };
+
+        let data = selected
+            .map(|access_point| sparkline_data(&access_point.history))
+            .unwrap_or_default();
+
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .title_bottom(labels)
+                    .border_style(Style::new().yellow()),
+            )
+            .data(&data)
+            .style(Style::default().yellow());
+
+        frame.render_widget(sparkline, chunks[1]);
+
+        // Stats text
+        let stats_text = vec![
+            Line::from(vec!["CPU Usage: ".dark_gray(), "45%".green()]),
+            Line::from(vec!["Memory Free: ".dark_gray(), "128 KB".cyan()]),
+            Line::from(vec!["Uptime: ".dark_gray(), "1h 23m".yellow()]),
+            Line::from(""),
+            Line::from("System running smoothly ✓".green()),
+        ];
+
+        let stats = Paragraph::new(stats_text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(" System Stats ")
+                .border_style(Style::new().green()),
+        );
+
+        frame.render_widget(stats, chunks[2]);
+    }
+
+    /// Draw the channels tab with the usage of every 2.4 GHz channel
+    fn draw_channels_tab(&self, frame: &mut Frame, area: Rect) {
+        let scan = self.scan.lock().unwrap();
+
+        // Bar height is the strongest signal, the value is the number of access points, with a
+        // `*` if the channel overlaps another used one. Only odd channels are labelled, as the
+        // labels of 10 to 14 would run together.
+        // Used channels get at least one full row, as the value is not shown on lower bars.
+        let rows = u64::from(area.height.saturating_sub(3).max(1));
+        let min_value = 100_u64.div_ceil(rows);
+        let bars: Vec<Bar> = channel_usage(&scan.access_points)
+            .into_iter()
+            .map(|usage| {
+                let style = match usage.strongest {
+                    Some(_) if usage.overlapping => Style::default().red(),
+                    Some(rssi) => rssi_style(rssi),
+                    None => Style::default().dark_gray(),
+                };
+                let label = if usage.channel % 2 == 1 {
+                    usage.channel.to_string()
+                } else {
+                    String::new()
+                };
+                let marker = if usage.overlapping { "*" } else { "" };
+                Bar::default()
+                    .label(Line::from(label))
+                    .value(usage.strongest.map_or(0, |rssi| {
+                        ((i16::from(rssi) + 100).max(1) as u64).max(min_value)
+                    }))
+                    .text_value(format!("{}{marker}", usage.access_points))
+                    .style(style)
+                    .value_style(style.reversed())
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" Channels ")
+                    .title_bottom(Line::from(" APs, * overlap ".dark_gray()))
+                    .border_style(Style::new().cyan()),
+            )
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(2)
+            .bar_gap(0)
+            .max(100);
+
+        frame.render_widget(chart, area);
+    }
+
+    /// Draw the settings tab
+    fn draw_settings_tab(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = Setting::ALL
+            .iter()
+            .map(|&setting| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:<14}", setting.to_string())),
+                    Span::styled(
+                        format!("{:>24}", self.settings.value(setting)),
+                        Style::default().cyan(),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" Settings ")
+                    .title_bottom(
+                        Line::from(" BTN2 change, hold BTN1 next ".dark_gray()).centered(),
+                    )
+                    .border_style(Style::new().magenta()),
+            )
+            .style(Style::default().white())
+            .highlight_style(Style::default().reversed());
+
+        let selected = Setting::ALL
+            .iter()
+            .position(|&setting| setting == self.selected_setting);
+        let mut list_state = ListState::default().with_selected(selected);
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+
+    /// Draw the footer with status and button hints
+    fn draw_footer(&self, frame: &mut Frame, area: Rect) {
+        let button_info = match self.last_button {
+            Some(Button::Button1(ButtonPressType::Short)) => " [BTN1] ",
+            Some(Button::Button2(ButtonPressType::Short)) => " [BTN2] ",
+            _ => "",
+        };
+
+        let text = vec![Line::from(vec![
+            button_info.cyan(),
+            self.status_message.as_str().green(),
+        ])];
+
+        let footer = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::new().dark_gray()),
+            )
+            .alignment(Alignment::Center);
+
+        frame.render_widget(footer, area);
+
+        // Fill the bottom border while a button is held
+        let bottom_border = Rect {
+            x: area.x + 1,
+            y: area.bottom().saturating_sub(1),
+            width: area.width.saturating_sub(2),
+            height: area.height.min(1),
+        };
+        frame.render_widget(&self.hold_indicator, bottom_border);
+    }
+}
+
+/// Name of an access point, or a placeholder for hidden networks.
+fn display_ssid(access_point: &AccessPoint) -> &str {
+    if access_point.ssid.is_empty() {
+        "<hidden>"
+    } else {
+        &access_point.ssid
+    }
+}
+
+/// Vendor of an access point, from its BSSID.
+fn vendor(access_point: &AccessPoint) -> &'static str {
+    match vendor_for(&access_point.bssid) {
+        Some(vendor) => vendor,
+        None if access_point.bssid.is_locally_administered() => "Local",
+        None => "",
+    }
+}
+
+/// Scale signal strength samples relative to the weakest one, so small changes are visible.
+fn sparkline_data(history: &RssiHistory) -> Vec<u64> {
+    let floor = history.min().unwrap_or_default();
+    history
+        .iter()
+        .map(|rssi| (i16::from(rssi) - i16::from(floor) + 1) as u64)
+        .collect()
+}
+
+/// Format an optional signal strength in dBm.
+fn format_rssi(rssi: Option<i8>) -> String {
+    rssi.map_or("-".to_string(), |rssi| rssi.to_string())
+}
+
+/// Style for a signal strength in dBm: green for strong, yellow for fair and red for weak.
+fn rssi_style(rssi: i8) -> Style {
+    match rssi {
+        -60.. => Style::default().green(),
+        -75..=-61 => Style::default().yellow(),
+        _ => Style::default().red(),
+    }
+}
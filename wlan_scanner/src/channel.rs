@@ -0,0 +1,74 @@
+use std::ops::RangeInclusive;
+
+use crate::access_point::AccessPointStore;
+
+/// Channels of the 2.4 GHz band.
+pub const CHANNELS: RangeInclusive<u8> = 1..=14;
+
+/// Width of a 2.4 GHz channel in MHz.
+///
+/// Two channels overlap if their center frequencies are closer than this.
+pub const CHANNEL_WIDTH_MHZ: u16 = 22;
+
+/// Center frequency of a 2.4 GHz channel in MHz.
+///
+/// Channels 1 to 13 are 5 MHz apart, channel 14 is 12 MHz above channel 13.
+pub fn center_frequency(channel: u8) -> u16 {
+    match channel {
+        14 => 2484,
+        _ => 2407 + 5 * u16::from(channel),
+    }
+}
+
+/// Check if two different channels overlap.
+pub fn overlaps(a: u8, b: u8) -> bool {
+    a != b && center_frequency(a).abs_diff(center_frequency(b)) < CHANNEL_WIDTH_MHZ
+}
+
+/// Access points using a single channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelUsage {
+    /// Channel number.
+    pub channel: u8,
+    /// Number of access points on the channel.
+    pub access_points: usize,
+    /// Strongest signal strength in dBm on the channel, if it is used.
+    pub strongest: Option<i8>,
+    /// Whether an overlapping channel is used as well.
+    pub overlapping: bool,
+}
+
+/// Usage of every 2.4 GHz channel by the tracked access points.
+///
+/// Access points on other channels, e.g. in the 5 GHz band, are ignored.
+pub fn channel_usage(access_points: &AccessPointStore) -> Vec<ChannelUsage> {
+    let mut usage: Vec<ChannelUsage> = CHANNELS
+        .map(|channel| ChannelUsage {
+            channel,
+            access_points: 0,
+            strongest: None,
+            overlapping: false,
+        })
+        .collect();
+
+    for access_point in access_points.iter() {
+        if let Some(channel) = usage
+            .iter_mut()
+            .find(|usage| usage.channel == access_point.channel)
+        {
+            channel.access_points += 1;
+            channel.strongest = channel.strongest.max(Some(access_point.rssi));
+        }
+    }
+
+    let used: Vec<u8> = usage
+        .iter()
+        .filter(|usage| usage.access_points > 0)
+        .map(|usage| usage.channel)
+        .collect();
+    for channel in usage.iter_mut().filter(|usage| usage.access_points > 0) {
+        channel.overlapping = used.iter().any(|&other| overlaps(channel.channel, other));
+    }
+
+    usage
+}
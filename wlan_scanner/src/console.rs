@@ -0,0 +1,195 @@
+use core::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::Sender;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::access_point::{Filter, SortKey};
+use ratatui_mousefood_runtime::button::{Button, ButtonPressType};
+
+/// Time to wait before reading again when no input is available.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Stack size of the console thread.
+const CONSOLE_THREAD_STACK_SIZE: usize = 8192;
+
+/// Usage of all commands, printed by `help`.
+pub const HELP: &str = "\
+scan                      scan right away
+list                      list the networks on the Main tab
+sort rssi|ssid|channel|security
+filter all|named|open|channel <n>
+tab <1-4>                 switch to a tab
+press b1|b2 [short|long|hold|double|repeat]
+                          press a button
+press both|b1+b2|b2+b1 [short|long|hold]
+                          press both buttons, at once or one after the other
+dump log [csv|json]       dump the scan log
+clear log                 remove all entries from the scan log
+settings set interval <s> set the scan interval in seconds
+help                      show this help";
+
+/// Export format of the scan log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Csv,
+    Json,
+}
+
+/// Command read from the console.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Scan right away, instead of waiting for the scan interval.
+    Scan,
+    /// Print the networks as listed on the Main tab.
+    List,
+    /// Sort the network list.
+    Sort(SortKey),
+    /// Filter the network list.
+    Filter(Filter),
+    /// Switch to a tab, counting from 0.
+    Tab(usize),
+    /// Act as if a button was pressed.
+    Press(Button),
+    /// Print the scan log.
+    DumpLog(LogFormat),
+    /// Remove all entries from the scan log.
+    ClearLog,
+    /// Set the time between two scans.
+    SetInterval(Duration),
+    /// Print the usage of all commands.
+    Help,
+}
+
+/// Error returned by [`parse`] for invalid commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, try `help`", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse a command line, or return `None` for an empty line.
+///
+/// Words are separated by whitespace and are not case sensitive.
+pub fn parse(line: &str) -> Option<Result<Command, ParseError>> {
+    let line = line.to_lowercase();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let command = match words.as_slice() {
+        [] => return None,
+        ["scan"] => Ok(Command::Scan),
+        ["list"] => Ok(Command::List),
+        ["sort", key] => parse_sort_key(key).map(Command::Sort),
+        ["filter", "all"] => Ok(Command::Filter(Filter::All)),
+        ["filter", "named"] => Ok(Command::Filter(Filter::HideHidden)),
+        ["filter", "open"] => Ok(Command::Filter(Filter::OpenOnly)),
+        ["filter", "channel", channel] => parse_number(channel, 1..=14)
+            .map(|channel| Command::Filter(Filter::Channel(channel as u8))),
+        ["tab", tab] => parse_number(tab, 1..=4).map(|tab| Command::Tab(tab as usize - 1)),
+        ["press", button] => parse_button(button, "short").map(Command::Press),
+        ["press", button, press_type] => parse_button(button, press_type).map(Command::Press),
+        ["dump", "log"] | ["dump", "log", "csv"] => Ok(Command::DumpLog(LogFormat::Csv)),
+        ["dump", "log", "json"] => Ok(Command::DumpLog(LogFormat::Json)),
+        ["clear", "log"] => Ok(Command::ClearLog),
+        ["settings", "set", "interval", seconds] => parse_number(seconds, 1..=3600)
+            .map(|seconds| Command::SetInterval(Duration::from_secs(seconds.into()))),
+        ["help"] => Ok(Command::Help),
+        _ => Err(ParseError(format!("unknown command `{}`", words.join(" ")))),
+    };
+    Some(command)
+}
+
+/// Parse a sort key by name.
+fn parse_sort_key(key: &str) -> Result<SortKey, ParseError> {
+    match key {
+        "rssi" => Ok(SortKey::Rssi),
+        "ssid" => Ok(SortKey::Ssid),
+        "channel" => Ok(SortKey::Channel),
+        "security" => Ok(SortKey::Security),
+        _ => Err(ParseError(format!("unknown sort key `{key}`"))),
+    }
+}
+
+/// Parse a button press, e.g. `b1` and `long`.
+fn parse_button(button: &str, press_type: &str) -> Result<Button, ParseError> {
+    let press_type = match press_type {
+        "short" => ButtonPressType::Short,
+        "long" => ButtonPressType::Long,
+        "hold" => ButtonPressType::Hold,
+        "double" => ButtonPressType::Double,
+        "repeat" => ButtonPressType::Repeat,
+        _ => return Err(ParseError(format!("unknown press type `{press_type}`"))),
+    };
+    match button {
+        "b1" => Ok(Button::Button1(press_type)),
+        "b2" => Ok(Button::Button2(press_type)),
+        "both" => Ok(Button::Both(press_type)),
+        "b1+b2" => Ok(Button::Button1Then2(press_type)),
+        "b2+b1" => Ok(Button::Button2Then1(press_type)),
+        _ => Err(ParseError(format!("unknown button `{button}`"))),
+    }
+}
+
+/// Parse a number in the given range.
+fn parse_number(number: &str, range: std::ops::RangeInclusive<u32>) -> Result<u32, ParseError> {
+    number
+        .parse()
+        .ok()
+        .filter(|number| range.contains(number))
+        .ok_or_else(|| {
+            ParseError(format!(
+                "expected a number from {} to {}, got `{number}`",
+                range.start(),
+                range.end()
+            ))
+        })
+}
+
+/// Read commands line by line in a background thread, e.g. from the serial port.
+///
+/// Valid commands are sent to `commands`, errors are written to `output` right away. On the
+/// ESP32, `stdin` and `stdout` are connected to the USB serial port. The thread ends when the
+/// receiver of `commands` is dropped.
+pub fn spawn_console<R, W>(input: R, mut output: W, commands: Sender<Command>) -> JoinHandle<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write + Send + 'static,
+{
+    thread::Builder::new()
+        .name("console".to_string())
+        .stack_size(CONSOLE_THREAD_STACK_SIZE)
+        .spawn(move || {
+            let mut input = input;
+            let mut line = String::new();
+            loop {
+                match input.read_line(&mut line) {
+                    // No input yet, or the input is non-blocking
+                    Ok(0) => thread::sleep(POLL_INTERVAL),
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                    Err(_) => return,
+                    Ok(_) if !line.ends_with('\n') => continue,
+                    Ok(_) => {
+                        let command = parse(&line);
+                        line.clear();
+                        let sent = match command {
+                            Some(Ok(command)) => commands.send(command).is_ok(),
+                            Some(Err(e)) => {
+                                let _ =
+                                    writeln!(output, "error: {e}").and_then(|()| output.flush());
+                                true
+                            }
+                            None => true,
+                        };
+                        if !sent {
+                            return;
+                        }
+                    }
+                }
+            }
+        })
+        .expect("Failed to spawn console thread")
+}
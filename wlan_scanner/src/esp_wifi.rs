@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::wifi::{
+    AuthMethod, Bssid, Cipher, CountryCode, PhyModes, ScanError, ScanResult, SecondaryChannel,
+    WifiScanner,
+};
+use esp_idf_svc::eventloop::EspSystemEventLoop;
+use esp_idf_svc::hal::modem::Modem;
+use esp_idf_svc::nvs::EspDefaultNvsPartition;
+use esp_idf_svc::sys::{self, EspError, esp};
+use esp_idf_svc::wifi::config::{ScanConfig, ScanType};
+use esp_idf_svc::wifi::{BlockingWifi, ClientConfiguration, Configuration, EspWifi};
+
+/// Time spent on the channel by a single channel scan.
+const HUNT_DWELL_TIME: Duration = Duration::from_millis(60);
+
+impl From<EspError> for ScanError {
+    fn from(e: EspError) -> Self {
+        ScanError(e.to_string())
+    }
+}
+
+/// Convert an ESP-IDF authentication mode.
+#[allow(non_upper_case_globals)]
+fn auth_method(authmode: sys::wifi_auth_mode_t) -> AuthMethod {
+    match authmode {
+        sys::wifi_auth_mode_t_WIFI_AUTH_OPEN => AuthMethod::Open,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WEP => AuthMethod::Wep,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WPA_PSK => AuthMethod::Wpa,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WPA2_PSK => AuthMethod::Wpa2,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WPA_WPA2_PSK => AuthMethod::WpaWpa2,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WPA2_ENTERPRISE => AuthMethod::Wpa2Enterprise,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WPA3_PSK => AuthMethod::Wpa3,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WPA2_WPA3_PSK => AuthMethod::Wpa2Wpa3,
+        sys::wifi_auth_mode_t_WIFI_AUTH_WAPI_PSK => AuthMethod::Wapi,
+        _ => AuthMethod::Unknown,
+    }
+}
+
+/// Convert an ESP-IDF cipher type.
+#[allow(non_upper_case_globals)]
+fn cipher(cipher: sys::wifi_cipher_type_t) -> Cipher {
+    match cipher {
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_NONE => Cipher::None,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_WEP40 => Cipher::Wep40,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_WEP104 => Cipher::Wep104,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_TKIP => Cipher::Tkip,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_CCMP => Cipher::Ccmp,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_TKIP_CCMP => Cipher::TkipCcmp,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_GCMP => Cipher::Gcmp,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_GCMP256 => Cipher::Gcmp256,
+        sys::wifi_cipher_type_t_WIFI_CIPHER_TYPE_SMS4 => Cipher::Sms4,
+        _ => Cipher::Unknown,
+    }
+}
+
+/// Convert an ESP-IDF secondary channel.
+#[allow(non_upper_case_globals)]
+fn secondary_channel(second: sys::wifi_second_chan_t) -> SecondaryChannel {
+    match second {
+        sys::wifi_second_chan_t_WIFI_SECOND_CHAN_ABOVE => SecondaryChannel::Above,
+        sys::wifi_second_chan_t_WIFI_SECOND_CHAN_BELOW => SecondaryChannel::Below,
+        _ => SecondaryChannel::None,
+    }
+}
+
+impl From<&sys::wifi_ap_record_t> for ScanResult {
+    fn from(record: &sys::wifi_ap_record_t) -> Self {
+        let ssid_len = record
+            .ssid
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(record.ssid.len());
+        let [a, b, _] = record.country.cc.map(|c| c as u8);
+        let country =
+            (a.is_ascii_uppercase() && b.is_ascii_uppercase()).then_some(CountryCode([a, b]));
+
+        ScanResult {
+            ssid: String::from_utf8_lossy(&record.ssid[..ssid_len]).into_owned(),
+            bssid: Bssid(record.bssid),
+            rssi: record.rssi,
+            channel: record.primary,
+            secondary_channel: secondary_channel(record.second),
+            auth_method: auth_method(record.authmode),
+            pairwise_cipher: cipher(record.pairwise_cipher),
+            group_cipher: cipher(record.group_cipher),
+            phy_modes: PhyModes {
+                b: record.phy_11b() != 0,
+                g: record.phy_11g() != 0,
+                n: record.phy_11n() != 0,
+                lr: record.phy_lr() != 0,
+            },
+            country,
+        }
+    }
+}
+
+/// Scanner backed by the ESP32 Wi-Fi driver in station mode.
+pub struct EspScanner {
+    wifi: BlockingWifi<EspWifi<'static>>,
+}
+
+impl EspScanner {
+    /// Start the Wi-Fi driver in station mode, without connecting to any network.
+    pub fn new(
+        modem: Modem,
+        sys_loop: EspSystemEventLoop,
+        nvs: EspDefaultNvsPartition,
+    ) -> Result<Self, ScanError> {
+        let wifi = EspWifi::new(modem, sys_loop.clone(), Some(nvs))?;
+        let mut wifi = BlockingWifi::wrap(wifi, sys_loop)?;
+        wifi.set_configuration(&Configuration::Client(ClientConfiguration::default()))?;
+        wifi.start()?;
+        Ok(Self { wifi })
+    }
+}
+
+impl EspScanner {
+    /// Run a blocking scan and read the raw records of the access points that were found.
+    fn scan_with(&mut self, config: &ScanConfig) -> Result<Vec<ScanResult>, ScanError> {
+        self.wifi.wifi_mut().start_scan(config, true)?;
+
+        // The raw records have the ciphers, PHY modes and country, which `AccessPointInfo` lacks
+        let mut count: u16 = 0;
+        esp!(unsafe { sys::esp_wifi_scan_get_ap_num(&mut count) })?;
+        let mut records: Vec<sys::wifi_ap_record_t> = vec![Default::default(); count.into()];
+        esp!(unsafe { sys::esp_wifi_scan_get_ap_records(&mut count, records.as_mut_ptr()) })?;
+        records.truncate(count.into());
+
+        Ok(records.iter().map(ScanResult::from).collect())
+    }
+}
+
+impl WifiScanner for EspScanner {
+    fn scan(&mut self) -> Result<Vec<ScanResult>, ScanError> {
+        // Hidden networks are listed with an empty SSID, for the "No hidden" filter to hide
+        self.scan_with(&ScanConfig {
+            show_hidden: true,
+            ..Default::default()
+        })
+    }
+
+    fn scan_channel(&mut self, channel: u8) -> Result<Vec<ScanResult>, ScanError> {
+        // A short active scan of a single channel takes tens of milliseconds instead of seconds
+        self.scan_with(&ScanConfig {
+            channel: Some(channel),
+            scan_type: ScanType::Active {
+                min: HUNT_DWELL_TIME,
+                max: HUNT_DWELL_TIME,
+            },
+            show_hidden: true,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::access_point::AccessPoint;
+use crate::history::RssiHistory;
+use crate::wifi::{Bssid, ScanResult};
+
+/// Time between two scans while hunting.
+pub const HUNT_SCAN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Weight of a new sample in the smoothed signal strength.
+const SMOOTHING_FACTOR: f32 = 0.3;
+
+/// Number of scans the trend is measured over.
+const TREND_WINDOW: usize = 4;
+
+/// Change of the smoothed signal strength in dB over the trend window that counts as a trend.
+const TREND_THRESHOLD: f32 = 2.0;
+
+/// Direction the signal strength is heading in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// Getting stronger, so getting closer.
+    Rising,
+    /// Getting weaker, so moving away.
+    Falling,
+    /// No significant change.
+    Steady,
+}
+
+/// Signal hunt locked onto a single access point.
+///
+/// Only the channel of the access point is scanned, at a high rate, and the results are
+/// smoothed with an exponential moving average, so the signal strength can be used to find
+/// the access point physically.
+#[derive(Debug, Clone)]
+pub struct FoxHunt {
+    /// Network name of the hunted access point.
+    pub ssid: String,
+    /// MAC address of the hunted access point.
+    pub bssid: Bssid,
+    /// Channel that is scanned.
+    pub channel: u8,
+    /// Raw signal strengths of the scans that found the access point.
+    pub history: RssiHistory,
+    /// Number of scans in a row that did not find the access point.
+    pub missed_scans: u32,
+    /// Smoothed signal strengths of the last scans, oldest first.
+    smoothed: VecDeque<f32>,
+}
+
+impl FoxHunt {
+    /// Start hunting an access point.
+    pub fn new(access_point: &AccessPoint) -> Self {
+        let mut hunt = Self {
+            ssid: access_point.ssid.clone(),
+            bssid: access_point.bssid,
+            channel: access_point.channel,
+            history: RssiHistory::default(),
+            missed_scans: 0,
+            smoothed: VecDeque::with_capacity(TREND_WINDOW + 1),
+        };
+        hunt.push(access_point.rssi);
+        hunt
+    }
+
+    /// Update the hunt with the results of a scan of its channel.
+    pub fn apply(&mut self, results: &[ScanResult]) {
+        match results.iter().find(|result| result.bssid == self.bssid) {
+            Some(result) => {
+                self.missed_scans = 0;
+                // It may have moved to another channel
+                self.channel = result.channel;
+                self.push(result.rssi);
+            }
+            None => self.missed_scans += 1,
+        }
+    }
+
+    /// Add a signal strength sample.
+    fn push(&mut self, rssi: i8) {
+        self.history.push(rssi);
+        let smoothed = match self.smoothed.back() {
+            Some(previous) => previous + SMOOTHING_FACTOR * (f32::from(rssi) - previous),
+            None => f32::from(rssi),
+        };
+        if self.smoothed.len() > TREND_WINDOW {
+            self.smoothed.pop_front();
+        }
+        self.smoothed.push_back(smoothed);
+    }
+
+    /// Smoothed signal strength in dBm.
+    pub fn smoothed_rssi(&self) -> Option<i8> {
+        self.smoothed.back().map(|rssi| rssi.round() as i8)
+    }
+
+    /// Direction the smoothed signal strength is heading in.
+    pub fn trend(&self) -> Trend {
+        let (Some(oldest), Some(latest)) = (self.smoothed.front(), self.smoothed.back()) else {
+            return Trend::Steady;
+        };
+        match latest - oldest {
+            change if change >= TREND_THRESHOLD => Trend::Rising,
+            change if change <= -TREND_THRESHOLD => Trend::Falling,
+            _ => Trend::Steady,
+        }
+    }
+}
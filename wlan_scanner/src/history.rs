@@ -0,0 +1,67 @@
+/// Number of RSSI samples kept per access point.
+pub const RSSI_HISTORY_LEN: usize = 32;
+
+/// Fixed-size ring buffer of signal strength samples in dBm.
+///
+/// Once full, every new sample overwrites the oldest one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RssiHistory {
+    samples: [i8; RSSI_HISTORY_LEN],
+    /// Index of the oldest sample.
+    start: usize,
+    /// Number of stored samples.
+    len: usize,
+}
+
+impl Default for RssiHistory {
+    fn default() -> Self {
+        Self {
+            samples: [0; RSSI_HISTORY_LEN],
+            start: 0,
+            len: 0,
+        }
+    }
+}
+
+impl RssiHistory {
+    /// Add a sample, dropping the oldest one if the buffer is full.
+    pub fn push(&mut self, rssi: i8) {
+        if self.len < RSSI_HISTORY_LEN {
+            self.samples[(self.start + self.len) % RSSI_HISTORY_LEN] = rssi;
+            self.len += 1;
+        } else {
+            self.samples[self.start] = rssi;
+            self.start = (self.start + 1) % RSSI_HISTORY_LEN;
+        }
+    }
+
+    /// Iterate over the samples, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = i8> + '_ {
+        (0..self.len).map(|i| self.samples[(self.start + i) % RSSI_HISTORY_LEN])
+    }
+
+    /// Number of stored samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if no samples are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Most recent sample.
+    pub fn latest(&self) -> Option<i8> {
+        self.iter().last()
+    }
+
+    /// Weakest stored sample.
+    pub fn min(&self) -> Option<i8> {
+        self.iter().min()
+    }
+
+    /// Strongest stored sample.
+    pub fn max(&self) -> Option<i8> {
+        self.iter().max()
+    }
+}
@@ -1,5 +1,33 @@
-/// Application setup.
-pub mod setup;
+/// Application state and UI.
+pub mod app;
 
-/// Button handling.
-pub mod button;
+/// Access points tracked across scans.
+pub mod access_point;
+
+/// 2.4 GHz channel usage.
+pub mod channel;
+
+/// Serial command console.
+pub mod console;
+
+/// Signal hunt locked onto one access point.
+pub mod fox_hunt;
+
+/// Signal strength history.
+pub mod history;
+
+/// Persistent log of scan results.
+pub mod scan_log;
+
+/// Persistent user settings.
+pub mod settings;
+
+/// MAC address vendor lookup.
+pub mod vendor;
+
+/// Wi-Fi scanning.
+pub mod wifi;
+
+/// Wi-Fi scanning with the ESP32 driver.
+#[cfg(feature = "esp")]
+pub mod esp_wifi;
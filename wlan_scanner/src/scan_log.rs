@@ -0,0 +1,238 @@
+use core::fmt;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use ratatui_mousefood_runtime::storage::{Namespace, StorageError};
+use serde::{Deserialize, Serialize};
+
+use crate::wifi::{AuthMethod, Bssid, ScanResult};
+
+/// Default number of entries kept in the log.
+///
+/// With at most 52 bytes per entry, a full log fits into the default 24 kB NVS partition,
+/// next to the data of the Wi-Fi driver.
+pub const DEFAULT_LOG_CAPACITY: usize = 128;
+
+/// Number of scans that are only kept in memory before the log is saved.
+///
+/// Every save writes the whole log to the flash, so with the default scan interval it is
+/// saved once a minute instead of after every scan.
+pub const SAVE_EVERY: u32 = 6;
+
+/// Version of the stored log entries.
+///
+/// Bump it when changing [`LogEntry`]. A stored log with another version is discarded.
+const LOG_VERSION: u8 = 1;
+
+/// Storage key of the log version.
+const VERSION_KEY: &str = "version";
+
+/// Storage key of the log entries.
+const ENTRIES_KEY: &str = "entries";
+
+/// Access point found by a logged scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Number of the scan, counting on across restarts.
+    pub scan: u32,
+    /// Time of the scan in milliseconds since the log was opened.
+    pub uptime_ms: u32,
+    /// MAC address of the access point.
+    pub bssid: Bssid,
+    /// Network name, empty for hidden networks.
+    pub ssid: String,
+    /// Signal strength in dBm.
+    pub rssi: i8,
+    /// Primary channel.
+    pub channel: u8,
+    /// Authentication method.
+    pub auth_method: AuthMethod,
+}
+
+/// Bounded log of scan results, kept in persistent storage.
+///
+/// Every scan appends one entry per access point that was found, and the oldest entries are
+/// dropped once the log is full. The log is saved every [`SAVE_EVERY`] scans, when it is
+/// dumped or cleared, and when it is dropped.
+pub struct ScanLog {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    /// Number of the last logged scan.
+    last_scan: u32,
+    /// Number of scans that were appended since the log was last saved.
+    unsaved: u32,
+    started: Instant,
+    namespace: Namespace,
+}
+
+impl ScanLog {
+    /// Open the log in `namespace` at `now`, keeping at most `capacity` entries.
+    ///
+    /// The uptime of new entries counts from `now`. A stored log from another version is
+    /// discarded.
+    pub fn open(namespace: Namespace, capacity: usize, now: Instant) -> Result<Self, StorageError> {
+        let mut entries: VecDeque<LogEntry> = match namespace.get::<u8>(VERSION_KEY)? {
+            Some(LOG_VERSION) => namespace.get(ENTRIES_KEY)?.unwrap_or_default(),
+            _ => VecDeque::new(),
+        };
+        while entries.len() > capacity {
+            entries.pop_front();
+        }
+        let last_scan = entries.back().map_or(0, |entry| entry.scan);
+
+        Ok(Self {
+            entries,
+            capacity,
+            last_scan,
+            unsaved: 0,
+            started: now,
+            namespace,
+        })
+    }
+
+    /// Append the results of a scan done at `now`.
+    ///
+    /// Once [`SAVE_EVERY`] scans were appended, the log is staged and its namespace is returned,
+    /// for the caller to commit after releasing any lock on the log, since that writes to the
+    /// flash.
+    pub fn append(
+        &mut self,
+        results: &[ScanResult],
+        now: Instant,
+    ) -> Result<Option<Namespace>, StorageError> {
+        self.last_scan += 1;
+        let uptime_ms = now.duration_since(self.started).as_millis() as u32;
+        for result in results {
+            if self.entries.len() == self.capacity {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(LogEntry {
+                scan: self.last_scan,
+                uptime_ms,
+                bssid: result.bssid,
+                ssid: result.ssid.clone(),
+                rssi: result.rssi,
+                channel: result.channel,
+                auth_method: result.auth_method,
+            });
+        }
+        self.unsaved += 1;
+        if self.unsaved < SAVE_EVERY {
+            return Ok(None);
+        }
+        self.stage().map(Some)
+    }
+
+    /// Remove all entries, and stage the empty log.
+    ///
+    /// Returns the namespace to commit, like [`Self::append`].
+    pub fn clear(&mut self) -> Result<Namespace, StorageError> {
+        self.entries.clear();
+        self.stage()
+    }
+
+    /// Stage the entries with their version, and return the namespace to commit.
+    pub fn stage(&mut self) -> Result<Namespace, StorageError> {
+        self.namespace.put(VERSION_KEY, &LOG_VERSION)?;
+        self.namespace.put(ENTRIES_KEY, &self.entries)?;
+        self.unsaved = 0;
+        Ok(self.namespace.clone())
+    }
+
+    /// Save the entries right away.
+    pub fn save(&mut self) -> Result<(), StorageError> {
+        self.stage()?.commit()
+    }
+
+    /// Iterate over the entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the log has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the log as CSV with a header line.
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "scan,uptime_ms,bssid,ssid,rssi,channel,auth")?;
+        for entry in &self.entries {
+            writeln!(
+                w,
+                "{},{},{},\"{}\",{},{},{}",
+                entry.scan,
+                entry.uptime_ms,
+                entry.bssid,
+                entry.ssid.replace('"', "\"\""),
+                entry.rssi,
+                entry.channel,
+                entry.auth_method,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the log as a JSON array, with one entry per line.
+    pub fn write_json(&self, w: &mut impl Write) -> io::Result<()> {
+        write!(w, "[")?;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(
+                w,
+                "{separator}\n{{\"scan\":{},\"uptime_ms\":{},\"bssid\":\"{}\",\"ssid\":\"{}\",\"rssi\":{},\"channel\":{},\"auth\":\"{}\"}}",
+                entry.scan,
+                entry.uptime_ms,
+                entry.bssid,
+                json_escape(&entry.ssid),
+                entry.rssi,
+                entry.channel,
+                entry.auth_method,
+            )?;
+        }
+        writeln!(w, "\n]")
+    }
+}
+
+impl fmt::Debug for ScanLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScanLog")
+            .field("entries", &self.entries.len())
+            .field("capacity", &self.capacity)
+            .field("last_scan", &self.last_scan)
+            .field("unsaved", &self.unsaved)
+            .field("namespace", &self.namespace.name())
+            .finish()
+    }
+}
+
+impl Drop for ScanLog {
+    /// Save the scans that were appended since the last save.
+    fn drop(&mut self) {
+        if self.unsaved > 0
+            && let Err(e) = self.save()
+        {
+            eprintln!("Failed to save scan log: {e}");
+        }
+    }
+}
+
+/// Escape a string for a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,258 @@
+use core::fmt;
+use std::time::Duration;
+
+use mousefood::ratatui::style::Color;
+use ratatui_mousefood_runtime::storage::{Namespace, StorageError};
+use serde::{Deserialize, Serialize};
+
+use crate::access_point::SortKey;
+use crate::wifi::DEFAULT_SCAN_INTERVAL;
+
+/// Version of the stored settings.
+///
+/// Bump it when changing the fields, and convert the settings of the older versions in
+/// [`migrate`].
+pub const SETTINGS_VERSION: u8 = 1;
+
+/// Storage key of the settings version.
+const VERSION_KEY: &str = "version";
+
+/// Storage key of the settings.
+const SETTINGS_KEY: &str = "settings";
+
+/// Scan intervals in seconds to choose from on the Settings tab.
+const SCAN_INTERVALS: [u64; 6] = [2, 5, 10, 30, 60, 300];
+
+/// Backlight brightness levels in percent to choose from on the Settings tab.
+const BRIGHTNESS_LEVELS: [u8; 5] = [10, 25, 50, 75, 100];
+
+/// Idle timeouts in seconds to choose from on the Settings tab, 0 for never.
+const IDLE_TIMEOUTS: [u64; 6] = [0, 15, 30, 60, 120, 300];
+
+/// Long-press thresholds in milliseconds to choose from on the Settings tab.
+const LONG_PRESS_THRESHOLDS: [u64; 5] = [300, 500, 800, 1000, 1500];
+
+/// Themes, in the order they are cycled through.
+const THEMES: [Theme; 4] = [Theme::Workshop, Theme::Ocean, Theme::Forest, Theme::Mono];
+
+/// Color scheme of the UI.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    /// Yellow accents.
+    #[default]
+    Workshop,
+    /// Cyan accents.
+    Ocean,
+    /// Green accents.
+    Forest,
+    /// White accents.
+    Mono,
+}
+
+impl Theme {
+    /// The theme after this one, wrapping around at the end.
+    pub fn next(self) -> Self {
+        let i = THEMES.iter().position(|&theme| theme == self).unwrap();
+        THEMES[(i + 1) % THEMES.len()]
+    }
+
+    /// Color of the header and the selected tab.
+    pub fn accent(self) -> Color {
+        match self {
+            Theme::Workshop => Color::Yellow,
+            Theme::Ocean => Color::Cyan,
+            Theme::Forest => Color::Green,
+            Theme::Mono => Color::White,
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Workshop => write!(f, "Workshop"),
+            Theme::Ocean => write!(f, "Ocean"),
+            Theme::Forest => write!(f, "Forest"),
+            Theme::Mono => write!(f, "Mono"),
+        }
+    }
+}
+
+/// A single entry of the [`Settings`], as listed on the Settings tab.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    /// [`Settings::scan_interval`]
+    #[default]
+    ScanInterval,
+    /// [`Settings::sort`]
+    Sort,
+    /// [`Settings::brightness`]
+    Brightness,
+    /// [`Settings::theme`]
+    Theme,
+    /// [`Settings::idle_timeout`]
+    IdleTimeout,
+    /// [`Settings::long_press`]
+    LongPress,
+}
+
+impl Setting {
+    /// All settings, in the order they are listed.
+    pub const ALL: [Setting; 6] = [
+        Setting::ScanInterval,
+        Setting::Sort,
+        Setting::Brightness,
+        Setting::Theme,
+        Setting::IdleTimeout,
+        Setting::LongPress,
+    ];
+
+    /// The setting after this one, wrapping around at the end.
+    pub fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|&setting| setting == self)
+            .unwrap();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Setting::ScanInterval => write!(f, "Scan interval"),
+            Setting::Sort => write!(f, "Sort"),
+            Setting::Brightness => write!(f, "Brightness"),
+            Setting::Theme => write!(f, "Theme"),
+            Setting::IdleTimeout => write!(f, "Idle timeout"),
+            Setting::LongPress => write!(f, "Long press"),
+        }
+    }
+}
+
+/// User settings, kept in persistent storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    /// Time between two background scans.
+    pub scan_interval: Duration,
+    /// Order of the network list.
+    pub sort: SortKey,
+    /// Backlight brightness in percent.
+    pub brightness: u8,
+    /// Color scheme of the UI.
+    pub theme: Theme,
+    /// Time without a button press before the backlight is dimmed, `None` to never dim.
+    pub idle_timeout: Option<Duration>,
+    /// Time a button has to be held for a long press.
+    pub long_press: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            scan_interval: DEFAULT_SCAN_INTERVAL,
+            sort: SortKey::default(),
+            brightness: 100,
+            theme: Theme::default(),
+            idle_timeout: Some(Duration::from_secs(60)),
+            long_press: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings from `namespace`, or the defaults if nothing is stored yet.
+    ///
+    /// Settings of an older version are migrated. Settings of a newer version, e.g. from a
+    /// newer firmware, are an error.
+    pub fn load(namespace: &Namespace) -> Result<Self, StorageError> {
+        let settings = match namespace.get::<u8>(VERSION_KEY)? {
+            Some(version) => migrate(version, namespace)?,
+            None => None,
+        };
+        Ok(settings.unwrap_or_default())
+    }
+
+    /// Save the settings with their version to `namespace`.
+    ///
+    /// Settings of a newer version are not overwritten, so they are still there after going
+    /// back to the newer firmware.
+    pub fn save(&self, namespace: &Namespace) -> Result<(), StorageError> {
+        match namespace.get::<u8>(VERSION_KEY)? {
+            Some(version) if version > SETTINGS_VERSION => return Err(newer_version(version)),
+            _ => {}
+        }
+        namespace.put(VERSION_KEY, &SETTINGS_VERSION)?;
+        namespace.put(SETTINGS_KEY, self)?;
+        namespace.commit()
+    }
+
+    /// Change a setting to its next value, wrapping around at the end.
+    pub fn next_value(&mut self, setting: Setting) {
+        match setting {
+            Setting::ScanInterval => {
+                let secs = next_option(&SCAN_INTERVALS, self.scan_interval.as_secs());
+                self.scan_interval = Duration::from_secs(secs);
+            }
+            Setting::Sort => self.sort = self.sort.next(),
+            Setting::Brightness => {
+                self.brightness = next_option(&BRIGHTNESS_LEVELS, self.brightness);
+            }
+            Setting::Theme => self.theme = self.theme.next(),
+            Setting::IdleTimeout => {
+                let secs = self.idle_timeout.map_or(0, |timeout| timeout.as_secs());
+                let secs = next_option(&IDLE_TIMEOUTS, secs);
+                self.idle_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+            }
+            Setting::LongPress => {
+                let millis = self.long_press.as_millis() as u64;
+                self.long_press =
+                    Duration::from_millis(next_option(&LONG_PRESS_THRESHOLDS, millis));
+            }
+        }
+    }
+
+    /// Format the value of a setting for the Settings tab.
+    pub fn value(&self, setting: Setting) -> String {
+        match setting {
+            Setting::ScanInterval => format!("{}s", self.scan_interval.as_secs()),
+            Setting::Sort => self.sort.to_string(),
+            Setting::Brightness => format!("{}%", self.brightness),
+            Setting::Theme => self.theme.to_string(),
+            Setting::IdleTimeout => match self.idle_timeout {
+                Some(timeout) => format!("{}s", timeout.as_secs()),
+                None => "Never".to_string(),
+            },
+            Setting::LongPress => format!("{}ms", self.long_press.as_millis()),
+        }
+    }
+}
+
+/// Read the settings stored with `version` from `namespace`, converted to the current version.
+///
+/// Versions before 1 were never saved, so there is nothing to read for them.
+fn migrate(version: u8, namespace: &Namespace) -> Result<Option<Settings>, StorageError> {
+    match version {
+        SETTINGS_VERSION => namespace.get(SETTINGS_KEY),
+        version if version > SETTINGS_VERSION => Err(newer_version(version)),
+        _ => Ok(None),
+    }
+}
+
+/// Error for settings saved by a newer firmware.
+fn newer_version(version: u8) -> StorageError {
+    StorageError(format!(
+        "settings version {version} is newer than {SETTINGS_VERSION}"
+    ))
+}
+
+/// The first option after `current`, or the first option if there is none.
+///
+/// Values that are not options, e.g. set from the console, move on to the next larger one.
+fn next_option<T: Copy + PartialOrd>(options: &[T], current: T) -> T {
+    let next = match options.iter().position(|&option| option == current) {
+        Some(i) => options.get(i + 1),
+        None => options.iter().find(|&&option| option > current),
+    };
+    *next.unwrap_or(&options[0])
+}
@@ -0,0 +1,15 @@
+use crate::wifi::Bssid;
+
+include!(concat!(env!("OUT_DIR"), "/oui.rs"));
+
+/// Look up the vendor of an access point by the OUI of its BSSID.
+///
+/// Returns `None` for unknown vendors and for locally administered addresses, which are not
+/// assigned by a vendor.
+pub fn vendor_for(bssid: &Bssid) -> Option<&'static str> {
+    if bssid.is_locally_administered() {
+        return None;
+    }
+    let index = OUIS.binary_search(&bssid.oui()).ok()?;
+    Some(VENDORS[usize::from(VENDOR_INDEX[index])])
+}
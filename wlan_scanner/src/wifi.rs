@@ -0,0 +1,454 @@
+use core::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use ratatui_mousefood_runtime::clock::Clock;
+use ratatui_mousefood_runtime::storage::{Namespace, StorageError};
+use serde::{Deserialize, Serialize};
+
+use crate::access_point::AccessPointStore;
+use crate::fox_hunt::{FoxHunt, HUNT_SCAN_INTERVAL};
+use crate::scan_log::ScanLog;
+
+/// Default time between two scans.
+pub const DEFAULT_SCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Stack size of the scan thread, the Wi-Fi driver needs more than the default.
+const SCAN_THREAD_STACK_SIZE: usize = 8192;
+
+/// Authentication method advertised by an access point.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum AuthMethod {
+    Open,
+    Wep,
+    Wpa,
+    Wpa2,
+    WpaWpa2,
+    Wpa2Enterprise,
+    Wpa3,
+    Wpa2Wpa3,
+    Wapi,
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for AuthMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthMethod::Open => write!(f, "Open"),
+            AuthMethod::Wep => write!(f, "WEP"),
+            AuthMethod::Wpa => write!(f, "WPA"),
+            AuthMethod::Wpa2 => write!(f, "WPA2"),
+            AuthMethod::WpaWpa2 => write!(f, "WPA/2"),
+            AuthMethod::Wpa2Enterprise => write!(f, "WPA2-E"),
+            AuthMethod::Wpa3 => write!(f, "WPA3"),
+            AuthMethod::Wpa2Wpa3 => write!(f, "WPA2/3"),
+            AuthMethod::Wapi => write!(f, "WAPI"),
+            AuthMethod::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+/// Cipher suite used by an access point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cipher {
+    None,
+    Wep40,
+    Wep104,
+    Tkip,
+    Ccmp,
+    TkipCcmp,
+    Gcmp,
+    Gcmp256,
+    Sms4,
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cipher::None => write!(f, "None"),
+            Cipher::Wep40 => write!(f, "WEP40"),
+            Cipher::Wep104 => write!(f, "WEP104"),
+            Cipher::Tkip => write!(f, "TKIP"),
+            Cipher::Ccmp => write!(f, "CCMP"),
+            Cipher::TkipCcmp => write!(f, "TKIP+CCMP"),
+            Cipher::Gcmp => write!(f, "GCMP"),
+            Cipher::Gcmp256 => write!(f, "GCMP256"),
+            Cipher::Sms4 => write!(f, "SMS4"),
+            Cipher::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+/// Position of the secondary channel of a 40 MHz access point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecondaryChannel {
+    /// 20 MHz only.
+    #[default]
+    None,
+    /// Above the primary channel (HT40+).
+    Above,
+    /// Below the primary channel (HT40-).
+    Below,
+}
+
+impl SecondaryChannel {
+    /// Number of the secondary channel for the given primary channel.
+    pub fn channel(self, primary: u8) -> Option<u8> {
+        match self {
+            SecondaryChannel::None => None,
+            SecondaryChannel::Above => Some(primary + 4),
+            SecondaryChannel::Below => primary.checked_sub(4),
+        }
+    }
+}
+
+/// 802.11 PHY modes supported by an access point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhyModes {
+    /// 802.11b.
+    pub b: bool,
+    /// 802.11g.
+    pub g: bool,
+    /// 802.11n.
+    pub n: bool,
+    /// Espressif long range mode.
+    pub lr: bool,
+}
+
+impl fmt::Display for PhyModes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modes: Vec<&str> = [(self.b, "b"), (self.g, "g"), (self.n, "n"), (self.lr, "LR")]
+            .into_iter()
+            .filter_map(|(supported, name)| supported.then_some(name))
+            .collect();
+        if modes.is_empty() {
+            write!(f, "?")
+        } else {
+            write!(f, "{}", modes.join("/"))
+        }
+    }
+}
+
+/// Two letter country code advertised by an access point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CountryCode(pub [u8; 2]);
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b] = self.0;
+        write!(f, "{}{}", char::from(a), char::from(b))
+    }
+}
+
+/// MAC address of an access point.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Bssid(pub [u8; 6]);
+
+impl Bssid {
+    /// Organizationally unique identifier, the first three bytes.
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// Check if the address is locally administered, i.e. not assigned by a vendor.
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+}
+
+impl fmt::Display for Bssid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02X}:{b:02X}:{c:02X}:{d:02X}:{e:02X}:{g:02X}")
+    }
+}
+
+/// Access point found by a scan.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScanResult {
+    /// Network name, empty for hidden networks.
+    pub ssid: String,
+    /// MAC address of the access point.
+    pub bssid: Bssid,
+    /// Signal strength in dBm.
+    pub rssi: i8,
+    /// Primary channel.
+    pub channel: u8,
+    /// Secondary channel of a 40 MHz access point.
+    pub secondary_channel: SecondaryChannel,
+    /// Authentication method.
+    pub auth_method: AuthMethod,
+    /// Cipher suite for unicast traffic.
+    pub pairwise_cipher: Cipher,
+    /// Cipher suite for broadcast traffic.
+    pub group_cipher: Cipher,
+    /// Supported PHY modes.
+    pub phy_modes: PhyModes,
+    /// Advertised country, if any.
+    pub country: Option<CountryCode>,
+}
+
+/// Error returned by a [`WifiScanner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError(pub String);
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "scan failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// Source of Wi-Fi scan results.
+pub trait WifiScanner {
+    /// Scan all channels and return the access points that were found.
+    fn scan(&mut self) -> Result<Vec<ScanResult>, ScanError>;
+
+    /// Scan a single channel and return the access points that were found.
+    ///
+    /// By default all channels are scanned and the results are filtered, scanners that can
+    /// scan a single channel faster should override it.
+    fn scan_channel(&mut self, channel: u8) -> Result<Vec<ScanResult>, ScanError> {
+        let mut results = self.scan()?;
+        results.retain(|result| result.channel == channel);
+        Ok(results)
+    }
+}
+
+/// Deterministic scanner for the simulator and tests.
+///
+/// It returns the same set of networks on every scan, with a signal strength that varies
+/// slightly from scan to scan. One network is only visible on every other scan.
+#[derive(Debug, Default)]
+pub struct MockScanner {
+    scan_count: u32,
+}
+
+impl MockScanner {
+    /// Networks returned by the mock: SSID, BSSID, RSSI, channel and authentication.
+    ///
+    /// The BSSIDs use real vendor OUIs, except for the hidden network, which has a locally
+    /// administered address.
+    #[rustfmt::skip]
+    const NETWORKS: [(&'static str, [u8; 6], i8, u8, AuthMethod); 6] = [
+        ("Ratatouille", [0x14, 0xCC, 0x20, 0x10, 0x00, 0x01], -42, 1, AuthMethod::Wpa2),
+        ("Gusteau's", [0xF0, 0x9F, 0xC2, 0x10, 0x00, 0x02], -55, 6, AuthMethod::Wpa2Wpa3),
+        ("Remy-Guest", [0x00, 0x18, 0x0A, 0x10, 0x00, 0x03], -61, 6, AuthMethod::Open),
+        ("", [0x02, 0x00, 0x5E, 0x10, 0x00, 0x04], -70, 11, AuthMethod::Wpa2),
+        ("Le Ventre", [0x00, 0x24, 0x01, 0x10, 0x00, 0x05], -78, 3, AuthMethod::Wep),
+        ("Skinner-IoT", [0x24, 0x0A, 0xC4, 0x10, 0x00, 0x06], -85, 13, AuthMethod::WpaWpa2),
+    ];
+
+    /// Ciphers that go with an authentication method.
+    fn ciphers(auth_method: AuthMethod) -> (Cipher, Cipher) {
+        match auth_method {
+            AuthMethod::Open => (Cipher::None, Cipher::None),
+            AuthMethod::Wep => (Cipher::Wep104, Cipher::Wep104),
+            AuthMethod::WpaWpa2 => (Cipher::TkipCcmp, Cipher::Tkip),
+            _ => (Cipher::Ccmp, Cipher::Ccmp),
+        }
+    }
+
+    /// Create a new mock scanner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl WifiScanner for MockScanner {
+    fn scan(&mut self) -> Result<Vec<ScanResult>, ScanError> {
+        let scan = self.scan_count;
+        self.scan_count += 1;
+
+        Ok(Self::NETWORKS
+            .iter()
+            .enumerate()
+            // The last network comes and goes
+            .filter(|(i, _)| *i + 1 < Self::NETWORKS.len() || scan.is_multiple_of(2))
+            .map(|(i, &(ssid, bssid, rssi, channel, auth_method))| {
+                let wobble = ((scan as usize * 7 + i * 3) % 9) as i8 - 4;
+                let (pairwise_cipher, group_cipher) = Self::ciphers(auth_method);
+                ScanResult {
+                    ssid: ssid.to_string(),
+                    bssid: Bssid(bssid),
+                    rssi: rssi + wobble,
+                    channel,
+                    // The strongest network uses 40 MHz
+                    secondary_channel: if i == 0 {
+                        SecondaryChannel::Above
+                    } else {
+                        SecondaryChannel::None
+                    },
+                    auth_method,
+                    pairwise_cipher,
+                    group_cipher,
+                    phy_modes: PhyModes {
+                        b: true,
+                        g: true,
+                        n: auth_method != AuthMethod::Wep,
+                        lr: false,
+                    },
+                    country: Some(CountryCode(*b"FR")),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Results of the scans, shared between the scan thread and the UI.
+#[derive(Debug)]
+pub struct ScanState {
+    /// Access points of all successful scans.
+    pub access_points: AccessPointStore,
+    /// Error of the last scan, if it failed.
+    pub last_error: Option<ScanError>,
+    /// Time between two scans.
+    pub interval: Duration,
+    /// Signal hunt in progress, only its channel is scanned while it is set.
+    pub hunt: Option<FoxHunt>,
+    /// Persistent log that every successful scan is appended to.
+    pub log: Option<ScanLog>,
+    /// Whether a scan finished since [`Self::take_changed`] was last called.
+    changed: bool,
+}
+
+impl Default for ScanState {
+    fn default() -> Self {
+        Self {
+            access_points: AccessPointStore::default(),
+            last_error: None,
+            interval: DEFAULT_SCAN_INTERVAL,
+            hunt: None,
+            log: None,
+            changed: false,
+        }
+    }
+}
+
+impl ScanState {
+    /// Check if a scan finished since the last call, e.g. to redraw the UI.
+    pub fn take_changed(&mut self) -> bool {
+        mem::take(&mut self.changed)
+    }
+
+    /// Store the outcome of a scan that finished at `now`.
+    ///
+    /// Returns the namespace of the [`ScanLog`] once it is due to be saved, see
+    /// [`ScanLog::append`]. Commit it after releasing the lock on the state, so drawing does
+    /// not wait for the flash.
+    pub fn apply(
+        &mut self,
+        result: Result<Vec<ScanResult>, ScanError>,
+        now: Instant,
+    ) -> Option<Namespace> {
+        self.changed = true;
+        let mut save = None;
+        match result {
+            Ok(results) => {
+                self.last_error = None;
+                if let Some(log) = &mut self.log {
+                    match log.append(&results, now) {
+                        Ok(namespace) => save = namespace,
+                        Err(e) => self.last_error = Some(ScanError(e.to_string())),
+                    }
+                }
+                self.access_points.merge(results, now);
+            }
+            Err(e) => self.last_error = Some(e),
+        }
+        save
+    }
+
+    /// Show an error of saving the scan log, which is committed outside of the lock.
+    pub fn save_failed(&mut self, e: StorageError) {
+        self.last_error = Some(ScanError(e.to_string()));
+        self.changed = true;
+    }
+
+    /// Store the outcome of a scan of the hunted channel.
+    ///
+    /// The results only update the hunt, not the access points, since they lack all other
+    /// channels. They are dropped if the hunt ended during the scan.
+    pub fn apply_hunt(&mut self, result: Result<Vec<ScanResult>, ScanError>) {
+        self.changed = true;
+        match result {
+            Ok(results) => {
+                if let Some(hunt) = &mut self.hunt {
+                    hunt.apply(&results);
+                }
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(e),
+        }
+    }
+}
+
+/// Scan in a background thread, so slow scans do not block the UI.
+///
+/// The scanner is created by `make_scanner` on the scan thread, so it does not need to be
+/// `Send` itself. After every scan the thread sleeps for the [`ScanState::interval`] that is
+/// current at that time, or until it is unparked. During a [`ScanState::hunt`] only the
+/// hunted channel is scanned, every [`HUNT_SCAN_INTERVAL`]. The scans are timed by `clock`.
+pub fn spawn_scanner<S, F, C>(
+    make_scanner: F,
+    state: Arc<Mutex<ScanState>>,
+    clock: C,
+) -> JoinHandle<()>
+where
+    S: WifiScanner,
+    F: FnOnce() -> Result<S, ScanError> + Send + 'static,
+    C: Clock + Send + 'static,
+{
+    thread::Builder::new()
+        .name("wifi-scan".to_string())
+        .stack_size(SCAN_THREAD_STACK_SIZE)
+        .spawn(move || {
+            let mut scanner = match make_scanner() {
+                Ok(scanner) => scanner,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(e);
+                    state.changed = true;
+                    return;
+                }
+            };
+
+            loop {
+                let hunted_channel = state.lock().unwrap().hunt.as_ref().map(|hunt| hunt.channel);
+                let interval = match hunted_channel {
+                    Some(channel) => {
+                        let result = scanner.scan_channel(channel);
+                        state.lock().unwrap().apply_hunt(result);
+                        HUNT_SCAN_INTERVAL
+                    }
+                    None => {
+                        let result = scanner.scan();
+                        let now = clock.now();
+                        let (interval, save) = {
+                            let mut state = state.lock().unwrap();
+                            let save = state.apply(result, now);
+                            (state.interval, save)
+                        };
+                        if let Some(namespace) = save
+                            && let Err(e) = namespace.commit()
+                        {
+                            state.lock().unwrap().save_failed(e);
+                        }
+                        interval
+                    }
+                };
+                thread::park_timeout(interval);
+            }
+        })
+        .expect("Failed to spawn scan thread")
+}
@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use ratatui_wlan_scanner::access_point::{AccessPointStore, Filter, SortKey};
+use ratatui_wlan_scanner::wifi::{AuthMethod, Bssid, ScanResult};
+
+fn network(last_byte: u8, rssi: i8) -> ScanResult {
+    ScanResult {
+        ssid: format!("Network {last_byte}"),
+        bssid: Bssid([0, 0, 0, 0, 0, last_byte]),
+        rssi,
+        channel: 6,
+        auth_method: AuthMethod::Wpa2,
+        ..ScanResult::default()
+    }
+}
+
+#[test]
+fn merges_scans_by_bssid() {
+    let start = Instant::now();
+    let later = start + Duration::from_secs(10);
+    let mut store = AccessPointStore::new(3);
+
+    store.merge(vec![network(1, -50), network(2, -70)], start);
+    store.merge(vec![network(1, -60)], later);
+
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.scan_count(), 2);
+
+    let access_point = store.get(&Bssid([0, 0, 0, 0, 0, 1])).unwrap();
+    assert_eq!(access_point.seen_count, 2);
+    assert_eq!(access_point.rssi, -60);
+    assert_eq!(access_point.rssi_min, -60);
+    assert_eq!(access_point.rssi_max, -50);
+    assert_eq!(access_point.rssi_average(), -55);
+    assert_eq!(access_point.first_seen, start);
+    assert_eq!(access_point.last_seen, later);
+    assert!(!store.is_missing(access_point));
+
+    let missing = store.get(&Bssid([0, 0, 0, 0, 0, 2])).unwrap();
+    assert_eq!(missing.seen_count, 1);
+    assert!(store.is_missing(missing));
+}
+
+#[test]
+fn reports_appeared_access_points() {
+    let now = Instant::now();
+    let mut store = AccessPointStore::new(3);
+
+    store.merge(vec![network(1, -50)], now);
+    store.merge(vec![network(1, -50), network(2, -70)], now);
+
+    let appeared: Vec<_> = store.appeared().map(|ap| ap.bssid).collect();
+    assert_eq!(appeared, [Bssid([0, 0, 0, 0, 0, 2])]);
+    assert!(store.is_new(store.get(&Bssid([0, 0, 0, 0, 0, 2])).unwrap()));
+    assert!(!store.is_new(store.get(&Bssid([0, 0, 0, 0, 0, 1])).unwrap()));
+}
+
+#[test]
+fn ages_out_access_points_after_missed_scans() {
+    let now = Instant::now();
+    let mut store = AccessPointStore::new(2);
+
+    store.merge(vec![network(1, -50), network(2, -70)], now);
+    store.merge(vec![network(1, -50)], now);
+    assert_eq!(store.len(), 2);
+    assert!(store.vanished().is_empty());
+
+    store.merge(vec![network(1, -50)], now);
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.vanished().len(), 1);
+    assert_eq!(store.vanished()[0].bssid, Bssid([0, 0, 0, 0, 0, 2]));
+
+    store.merge(vec![network(1, -50)], now);
+    assert!(store.vanished().is_empty());
+}
+
+#[test]
+fn lists_access_points_by_sort_key() {
+    let mut store = AccessPointStore::new(3);
+    let mut hidden = network(1, -40);
+    hidden.ssid = String::new();
+    let mut open = network(2, -80);
+    open.auth_method = AuthMethod::Open;
+    open.channel = 11;
+    let mut other = network(3, -60);
+    other.channel = 1;
+    store.merge(vec![hidden, open, other], Instant::now());
+
+    let order = |sort| -> Vec<u8> {
+        store
+            .list(sort, Filter::All)
+            .iter()
+            .map(|ap| ap.bssid.0[5])
+            .collect()
+    };
+    assert_eq!(order(SortKey::Rssi), [1, 3, 2]);
+    assert_eq!(order(SortKey::Ssid), [2, 3, 1]);
+    assert_eq!(order(SortKey::Channel), [3, 1, 2]);
+    assert_eq!(order(SortKey::Security), [2, 1, 3]);
+}
+
+#[test]
+fn filters_access_points() {
+    let mut store = AccessPointStore::new(3);
+    let mut hidden = network(1, -40);
+    hidden.ssid = String::new();
+    let mut open = network(2, -80);
+    open.auth_method = AuthMethod::Open;
+    open.channel = 11;
+    store.merge(vec![hidden, open, network(3, -60)], Instant::now());
+
+    let listed = |filter| -> Vec<u8> {
+        store
+            .list(SortKey::Rssi, filter)
+            .iter()
+            .map(|ap| ap.bssid.0[5])
+            .collect()
+    };
+    assert_eq!(listed(Filter::HideHidden), [3, 2]);
+    assert_eq!(listed(Filter::OpenOnly), [2]);
+    assert_eq!(listed(Filter::Channel(6)), [1, 3]);
+}
+
+#[test]
+fn cycles_filters_through_used_channels() {
+    let mut store = AccessPointStore::new(3);
+    let mut other = network(2, -60);
+    other.channel = 11;
+    store.merge(vec![network(1, -50), other], Instant::now());
+
+    let mut filter = Filter::All;
+    let mut filters = Vec::new();
+    for _ in 0..5 {
+        filter = filter.next(&store);
+        filters.push(filter);
+    }
+    assert_eq!(
+        filters,
+        [
+            Filter::HideHidden,
+            Filter::OpenOnly,
+            Filter::Channel(6),
+            Filter::Channel(11),
+            Filter::All,
+        ]
+    );
+}
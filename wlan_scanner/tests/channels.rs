@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+use ratatui_wlan_scanner::access_point::AccessPointStore;
+use ratatui_wlan_scanner::channel::{channel_usage, overlaps};
+use ratatui_wlan_scanner::wifi::{AuthMethod, Bssid, ScanResult};
+
+fn network(last_byte: u8, channel: u8, rssi: i8) -> ScanResult {
+    ScanResult {
+        ssid: format!("Network {last_byte}"),
+        bssid: Bssid([0, 0, 0, 0, 0, last_byte]),
+        rssi,
+        channel,
+        auth_method: AuthMethod::Wpa2,
+        ..ScanResult::default()
+    }
+}
+
+#[test]
+fn channels_five_apart_do_not_overlap() {
+    assert!(!overlaps(1, 6));
+    assert!(!overlaps(6, 11));
+    assert!(overlaps(1, 5));
+    assert!(overlaps(13, 14));
+    assert!(!overlaps(11, 14));
+    assert!(!overlaps(6, 6));
+}
+
+#[test]
+fn counts_access_points_per_channel() {
+    let mut store = AccessPointStore::default();
+    store.merge(
+        vec![
+            network(1, 6, -70),
+            network(2, 6, -50),
+            network(3, 11, -80),
+            network(4, 36, -40),
+        ],
+        Instant::now(),
+    );
+
+    let usage = channel_usage(&store);
+    assert_eq!(usage.len(), 14);
+
+    let channel_6 = usage[5];
+    assert_eq!(channel_6.channel, 6);
+    assert_eq!(channel_6.access_points, 2);
+    assert_eq!(channel_6.strongest, Some(-50));
+    assert!(!channel_6.overlapping);
+
+    let channel_1 = usage[0];
+    assert_eq!(channel_1.access_points, 0);
+    assert_eq!(channel_1.strongest, None);
+}
+
+#[test]
+fn marks_overlapping_channels() {
+    let mut store = AccessPointStore::default();
+    store.merge(
+        vec![network(1, 1, -60), network(2, 3, -60), network(3, 11, -60)],
+        Instant::now(),
+    );
+
+    let overlapping: Vec<u8> = channel_usage(&store)
+        .into_iter()
+        .filter(|usage| usage.overlapping)
+        .map(|usage| usage.channel)
+        .collect();
+    assert_eq!(overlapping, [1, 3]);
+}
@@ -0,0 +1,126 @@
+use std::time::{Duration, Instant};
+
+use ratatui_mousefood_runtime::button::{Button, ButtonPressType};
+use ratatui_mousefood_runtime::testing::Harness;
+use ratatui_wlan_scanner::access_point::{Filter, SortKey};
+use ratatui_wlan_scanner::app::AppState;
+use ratatui_wlan_scanner::console::{Command, LogFormat, parse};
+use ratatui_wlan_scanner::wifi::{MockScanner, WifiScanner};
+
+fn command(line: &str) -> Command {
+    parse(line).unwrap().unwrap()
+}
+
+fn error(line: &str) -> String {
+    parse(line).unwrap().unwrap_err().to_string()
+}
+
+#[test]
+fn parses_commands() {
+    assert_eq!(command("scan"), Command::Scan);
+    assert_eq!(command("list"), Command::List);
+    assert_eq!(command("sort rssi"), Command::Sort(SortKey::Rssi));
+    assert_eq!(command("sort Security"), Command::Sort(SortKey::Security));
+    assert_eq!(command("filter open"), Command::Filter(Filter::OpenOnly));
+    assert_eq!(
+        command("filter channel 6"),
+        Command::Filter(Filter::Channel(6))
+    );
+    assert_eq!(command("tab 2"), Command::Tab(1));
+    assert_eq!(
+        command("press b1 long"),
+        Command::Press(Button::Button1(ButtonPressType::Long))
+    );
+    assert_eq!(
+        command("press b2 hold"),
+        Command::Press(Button::Button2(ButtonPressType::Hold))
+    );
+    assert_eq!(
+        command("press b1 double"),
+        Command::Press(Button::Button1(ButtonPressType::Double))
+    );
+    assert_eq!(
+        command("press b2"),
+        Command::Press(Button::Button2(ButtonPressType::Short))
+    );
+    assert_eq!(
+        command("press both"),
+        Command::Press(Button::Both(ButtonPressType::Short))
+    );
+    assert_eq!(
+        command("press b2+b1 long"),
+        Command::Press(Button::Button2Then1(ButtonPressType::Long))
+    );
+    assert_eq!(command("dump log"), Command::DumpLog(LogFormat::Csv));
+    assert_eq!(command("dump log json"), Command::DumpLog(LogFormat::Json));
+    assert_eq!(command("clear log"), Command::ClearLog);
+    assert_eq!(
+        command("  settings   set interval 5 \r\n"),
+        Command::SetInterval(Duration::from_secs(5))
+    );
+    assert_eq!(command("help"), Command::Help);
+}
+
+#[test]
+fn ignores_empty_lines() {
+    assert_eq!(parse(""), None);
+    assert_eq!(parse(" \r\n"), None);
+}
+
+#[test]
+fn rejects_invalid_commands() {
+    assert_eq!(error("dance"), "unknown command `dance`, try `help`");
+    assert_eq!(
+        error("sort vendor"),
+        "unknown sort key `vendor`, try `help`"
+    );
+    assert_eq!(
+        error("tab 5"),
+        "expected a number from 1 to 4, got `5`, try `help`"
+    );
+    assert_eq!(error("press b3 long"), "unknown button `b3`, try `help`");
+    assert_eq!(
+        error("press b1 triple"),
+        "unknown press type `triple`, try `help`"
+    );
+    assert_eq!(
+        error("settings set interval soon"),
+        "expected a number from 1 to 3600, got `soon`, try `help`"
+    );
+}
+
+#[test]
+fn lists_networks() {
+    let mut app = AppState::new();
+    app.apply_scan(MockScanner::new().scan(), Instant::now());
+
+    let mut out = Vec::new();
+    app.run_command(command("sort ssid"), &mut out).unwrap();
+    app.run_command(command("list"), &mut out).unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "ok");
+    assert_eq!(lines.len(), 7);
+    assert!(lines[1].starts_with(" 1. F0:9F:C2:10:00:02"));
+    assert!(lines[1].ends_with("Gusteau's"));
+}
+
+#[test]
+fn drives_the_ui() {
+    let mut app = AppState::new();
+    app.apply_scan(MockScanner::new().scan(), Instant::now());
+    let mut harness = Harness::new(app);
+
+    let mut out = Vec::new();
+    harness
+        .app_mut()
+        .run_command(command("press b2"), &mut out)
+        .unwrap();
+    harness
+        .app_mut()
+        .run_command(command("tab 2"), &mut out)
+        .unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "ok\nok\n");
+    harness.assert_snapshot("console_stats_tab");
+}
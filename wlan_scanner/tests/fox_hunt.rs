@@ -0,0 +1,65 @@
+use std::time::Instant;
+
+use ratatui_wlan_scanner::access_point::AccessPointStore;
+use ratatui_wlan_scanner::fox_hunt::{FoxHunt, Trend};
+use ratatui_wlan_scanner::wifi::{Bssid, ScanResult};
+
+fn network(last_byte: u8, rssi: i8) -> ScanResult {
+    ScanResult {
+        ssid: format!("Network {last_byte}"),
+        bssid: Bssid([0, 0, 0, 0, 0, last_byte]),
+        rssi,
+        channel: 6,
+        ..ScanResult::default()
+    }
+}
+
+fn hunt(rssi: i8) -> FoxHunt {
+    let mut store = AccessPointStore::default();
+    store.merge(vec![network(1, rssi)], Instant::now());
+    FoxHunt::new(store.get(&Bssid([0, 0, 0, 0, 0, 1])).unwrap())
+}
+
+#[test]
+fn smooths_signal_strength() {
+    let mut hunt = hunt(-70);
+    assert_eq!(hunt.smoothed_rssi(), Some(-70));
+
+    hunt.apply(&[network(1, -50)]);
+    assert_eq!(hunt.smoothed_rssi(), Some(-64));
+    assert_eq!(hunt.history.latest(), Some(-50));
+}
+
+#[test]
+fn detects_trend() {
+    let mut hunt = hunt(-70);
+    assert_eq!(hunt.trend(), Trend::Steady);
+
+    for rssi in [-66, -62, -58, -54] {
+        hunt.apply(&[network(1, rssi)]);
+    }
+    assert_eq!(hunt.trend(), Trend::Rising);
+
+    for _ in 0..8 {
+        hunt.apply(&[network(1, -80)]);
+    }
+    assert_eq!(hunt.trend(), Trend::Falling);
+
+    for _ in 0..20 {
+        hunt.apply(&[network(1, -80)]);
+    }
+    assert_eq!(hunt.trend(), Trend::Steady);
+}
+
+#[test]
+fn counts_missed_scans() {
+    let mut hunt = hunt(-70);
+
+    hunt.apply(&[network(2, -40)]);
+    hunt.apply(&[]);
+    assert_eq!(hunt.missed_scans, 2);
+    assert_eq!(hunt.history.len(), 1);
+
+    hunt.apply(&[network(1, -60)]);
+    assert_eq!(hunt.missed_scans, 0);
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use ratatui_mousefood_runtime::storage::{FileStorage, Namespace, shared};
+use ratatui_mousefood_runtime::testing::storage_dir;
+use ratatui_wlan_scanner::app::AppState;
+use ratatui_wlan_scanner::console::{Command, LogFormat};
+use ratatui_wlan_scanner::scan_log::{SAVE_EVERY, ScanLog};
+use ratatui_wlan_scanner::wifi::{AuthMethod, Bssid, ScanResult};
+
+fn network(last_byte: u8, ssid: &str, rssi: i8) -> ScanResult {
+    ScanResult {
+        ssid: ssid.to_string(),
+        bssid: Bssid([0, 0, 0, 0, 0, last_byte]),
+        rssi,
+        channel: 6,
+        auth_method: AuthMethod::Wpa2,
+        ..ScanResult::default()
+    }
+}
+
+/// Open the scan log namespace in the storage directory `dir`, as after a restart.
+fn namespace(dir: &Path) -> Namespace {
+    Namespace::new(&shared(FileStorage::new(dir)), "scan_log")
+}
+
+#[test]
+fn keeps_log_across_restarts() {
+    let dir = storage_dir();
+    let mut log = ScanLog::open(namespace(dir.path()), 16, Instant::now()).unwrap();
+    log.append(&[network(1, "Ratatouille", -42)], Instant::now())
+        .unwrap();
+    log.append(&[network(1, "Ratatouille", -44)], Instant::now())
+        .unwrap();
+    drop(log);
+
+    let mut log = ScanLog::open(namespace(dir.path()), 16, Instant::now()).unwrap();
+    assert_eq!(log.len(), 2);
+    log.append(&[network(2, "Gusteau's", -55)], Instant::now())
+        .unwrap();
+
+    let scans: Vec<u32> = log.iter().map(|entry| entry.scan).collect();
+    assert_eq!(scans, [1, 2, 3]);
+    let last = log.iter().last().unwrap();
+    assert_eq!(last.ssid, "Gusteau's");
+    assert_eq!(last.rssi, -55);
+    assert_eq!(last.bssid, Bssid([0, 0, 0, 0, 0, 2]));
+}
+
+#[test]
+fn saves_log_every_few_scans() {
+    let dir = storage_dir();
+    let mut log = ScanLog::open(namespace(dir.path()), 16, Instant::now()).unwrap();
+    for _ in 1..SAVE_EVERY {
+        let save = log
+            .append(&[network(1, "Ratatouille", -42)], Instant::now())
+            .unwrap();
+        assert!(save.is_none());
+    }
+    assert!(
+        ScanLog::open(namespace(dir.path()), 16, Instant::now())
+            .unwrap()
+            .is_empty()
+    );
+
+    let save = log
+        .append(&[network(1, "Ratatouille", -42)], Instant::now())
+        .unwrap();
+    save.unwrap().commit().unwrap();
+    let saved = ScanLog::open(namespace(dir.path()), 16, Instant::now()).unwrap();
+    assert_eq!(saved.len(), SAVE_EVERY as usize);
+}
+
+#[test]
+fn drops_oldest_entries_when_full() {
+    let dir = storage_dir();
+    let mut log = ScanLog::open(namespace(dir.path()), 3, Instant::now()).unwrap();
+    for i in 1..=5 {
+        log.append(&[network(i, "Net", -50)], Instant::now())
+            .unwrap();
+    }
+
+    let bssids: Vec<u8> = log.iter().map(|entry| entry.bssid.0[5]).collect();
+    assert_eq!(bssids, [3, 4, 5]);
+}
+
+#[test]
+fn discards_log_of_other_version() {
+    let dir = storage_dir();
+    let mut log = ScanLog::open(namespace(dir.path()), 16, Instant::now()).unwrap();
+    log.append(&[network(1, "Ratatouille", -42)], Instant::now())
+        .unwrap();
+    let handle = namespace(dir.path());
+    handle.put("version", &99_u8).unwrap();
+    handle.commit().unwrap();
+
+    let log = ScanLog::open(handle, 16, Instant::now()).unwrap();
+    assert!(log.is_empty());
+}
+
+#[test]
+fn exports_csv_and_json() {
+    let dir = storage_dir();
+    let start = Instant::now();
+    let mut log = ScanLog::open(namespace(dir.path()), 16, start).unwrap();
+    log.append(
+        &[
+            network(1, "Ratatouille", -42),
+            network(2, "Say \"Hi\"", -60),
+        ],
+        start + Duration::from_millis(1500),
+    )
+    .unwrap();
+
+    let mut csv = Vec::new();
+    log.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "scan,uptime_ms,bssid,ssid,rssi,channel,auth");
+    assert_eq!(
+        lines[1],
+        "1,1500,00:00:00:00:00:01,\"Ratatouille\",-42,6,WPA2"
+    );
+    assert!(lines[2].ends_with(",00:00:00:00:00:02,\"Say \"\"Hi\"\"\",-60,6,WPA2"));
+
+    let mut json = Vec::new();
+    log.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    let lines: Vec<&str> = json.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "[");
+    assert!(lines[1].starts_with("{\"scan\":1,\"uptime_ms\":1500,"));
+    assert!(lines[1].ends_with(
+        "\"bssid\":\"00:00:00:00:00:01\",\"ssid\":\"Ratatouille\",\"rssi\":-42,\"channel\":6,\"auth\":\"WPA2\"},"
+    ));
+    assert!(lines[2].contains("\"ssid\":\"Say \\\"Hi\\\"\""));
+    assert_eq!(lines[3], "]");
+}
+
+#[test]
+fn console_dumps_and_clears_log() {
+    let dir = storage_dir();
+    let log = ScanLog::open(namespace(dir.path()), 16, Instant::now()).unwrap();
+    let mut app = AppState::new().with_scan_log(log);
+    app.apply_scan(Ok(vec![network(1, "Ratatouille", -42)]), Instant::now());
+
+    let mut out = Vec::new();
+    app.run_command(Command::DumpLog(LogFormat::Csv), &mut out)
+        .unwrap();
+    assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+
+    let mut out = Vec::new();
+    app.run_command(Command::ClearLog, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "ok\n");
+
+    let mut out = Vec::new();
+    app.run_command(Command::DumpLog(LogFormat::Json), &mut out)
+        .unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "[\n]\n");
+}
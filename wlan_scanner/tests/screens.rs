@@ -0,0 +1,180 @@
+use std::time::{Duration, Instant};
+
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::{Button, ButtonEvent, ButtonId, ButtonPressType};
+use ratatui_mousefood_runtime::command::Command;
+use ratatui_mousefood_runtime::testing::Harness;
+use ratatui_wlan_scanner::app::AppState;
+use ratatui_wlan_scanner::wifi::{MockScanner, WifiScanner};
+
+const NEXT_TAB: Button = Button::Button1(ButtonPressType::Short);
+const ACTION: Button = Button::Button2(ButtonPressType::Short);
+const NEXT_FILTER: Button = Button::Button1(ButtonPressType::Long);
+const NEXT_SORT: Button = Button::Both(ButtonPressType::Short);
+const OPEN_DETAIL: Button = Button::Button2(ButtonPressType::Long);
+const SCROLL: Button = Button::Button2(ButtonPressType::Repeat);
+
+/// App state after one scan with the mock scanner.
+fn scanned_app() -> AppState {
+    let mut app = AppState::new();
+    app.apply_scan(MockScanner::new().scan(), Instant::now());
+    app
+}
+
+#[test]
+fn main_tab_is_scanning_on_start() {
+    let mut harness = Harness::new(AppState::new());
+    harness.assert_snapshot("main_tab_scanning");
+}
+
+#[test]
+fn main_tab_lists_scan_results() {
+    let mut harness = Harness::new(scanned_app());
+    harness.assert_snapshot("main_tab");
+    harness.assert_png_snapshot("main_tab");
+}
+
+#[test]
+fn main_tab_marks_new_and_missing_networks() {
+    let mut app = AppState::new();
+    let mut scanner = MockScanner::new();
+    app.apply_scan(scanner.scan(), Instant::now());
+    app.apply_scan(scanner.scan(), Instant::now());
+    let mut harness = Harness::with_size(app, 40, 20);
+    harness.assert_snapshot("main_tab_missing");
+}
+
+#[test]
+fn main_tab_selects_next_network_on_action() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(ACTION).press(ACTION);
+    harness.assert_snapshot("main_tab_selected");
+}
+
+#[test]
+fn footer_fills_while_button_is_held() {
+    let mut harness = Harness::new(scanned_app());
+    harness
+        .event(ButtonEvent::Down(ButtonId::Button1))
+        .event(ButtonEvent::HoldProgress(
+            ButtonId::Button1,
+            Duration::from_millis(250),
+        ));
+    harness.assert_snapshot("footer_holding");
+}
+
+#[test]
+fn main_tab_scrolls_while_action_is_held() {
+    let mut harness = Harness::new(scanned_app());
+    assert!(
+        harness
+            .app()
+            .button_config(ButtonId::Button2)
+            .repeat
+            .is_some()
+    );
+    assert!(
+        harness
+            .app()
+            .button_config(ButtonId::Button1)
+            .repeat
+            .is_none()
+    );
+    harness.press(SCROLL).press(SCROLL);
+    harness.assert_snapshot("main_tab_scrolled");
+
+    harness.press(NEXT_TAB);
+    assert!(
+        harness
+            .app()
+            .button_config(ButtonId::Button2)
+            .repeat
+            .is_none()
+    );
+}
+
+#[test]
+fn main_tab_sorts_by_ssid_on_both_buttons() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(NEXT_SORT);
+    harness.assert_snapshot("main_tab_sorted_by_ssid");
+}
+
+#[test]
+fn display_sleeps_on_held_buttons() {
+    let mut harness = Harness::with_size(scanned_app(), 40, 20);
+    harness.press(Button::Both(ButtonPressType::Hold));
+    assert!(matches!(
+        harness.take_commands().as_slice(),
+        [Command::Sleep, Command::Redraw]
+    ));
+}
+
+#[test]
+fn main_tab_filters_open_networks_on_long_next_tab() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(NEXT_FILTER).press(NEXT_FILTER);
+    harness.assert_snapshot("main_tab_open_only");
+}
+
+#[test]
+fn detail_screen_opens_on_long_action() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(OPEN_DETAIL);
+    harness.assert_snapshot("detail_screen");
+}
+
+#[test]
+fn detail_screen_closes_on_next_tab() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(ACTION).press(OPEN_DETAIL).press(NEXT_TAB);
+    harness.assert_snapshot("main_tab_after_detail");
+}
+
+#[test]
+fn hunt_screen_shows_smoothed_signal() {
+    let mut scanner = MockScanner::new();
+    let mut app = AppState::new();
+    app.apply_scan(scanner.scan(), Instant::now());
+    let mut harness = Harness::new(app);
+    harness.press(OPEN_DETAIL).press(ACTION);
+    for _ in 0..8 {
+        harness
+            .app_mut()
+            .apply_scan(scanner.scan_channel(1), Instant::now());
+    }
+    harness.assert_snapshot("hunt_screen");
+}
+
+#[test]
+fn hunt_stops_on_next_tab() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(OPEN_DETAIL).press(ACTION).press(NEXT_TAB);
+    harness.assert_snapshot("detail_after_hunt");
+}
+
+#[test]
+fn stats_tab_shows_history_of_selected_network() {
+    let mut app = AppState::new();
+    let mut scanner = MockScanner::new();
+    for _ in 0..12 {
+        app.apply_scan(scanner.scan(), Instant::now());
+    }
+    let mut harness = Harness::new(app);
+    harness.press(ACTION).press(NEXT_TAB);
+    harness.assert_snapshot("stats_tab");
+}
+
+#[test]
+fn channels_tab_shows_usage_per_channel() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(NEXT_TAB).press(NEXT_TAB);
+    harness.assert_snapshot("channels_tab");
+}
+
+#[test]
+fn settings_tab_after_three_button1_presses() {
+    let mut harness = Harness::new(scanned_app());
+    harness.press(NEXT_TAB).press(NEXT_TAB).press(NEXT_TAB);
+    harness.assert_snapshot("settings_tab");
+}
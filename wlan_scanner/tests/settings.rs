@@ -0,0 +1,203 @@
+use std::path::Path;
+use std::time::Duration;
+
+use ratatui_mousefood_runtime::app::App;
+use ratatui_mousefood_runtime::button::{Button, ButtonId, ButtonPressType};
+use ratatui_mousefood_runtime::storage::{FileStorage, Namespace, shared};
+use ratatui_mousefood_runtime::testing::{Harness, storage_dir};
+use ratatui_wlan_scanner::access_point::SortKey;
+use ratatui_wlan_scanner::app::AppState;
+use ratatui_wlan_scanner::console::Command;
+use ratatui_wlan_scanner::settings::{Setting, Settings, Theme};
+
+const NEXT_TAB: Button = Button::Button1(ButtonPressType::Short);
+const NEXT_VALUE: Button = Button::Button2(ButtonPressType::Short);
+const NEXT_SETTING: Button = Button::Button1(ButtonPressType::Long);
+
+/// Open the settings namespace in the storage directory `dir`, as after a restart.
+fn namespace(dir: &Path) -> Namespace {
+    Namespace::new(&shared(FileStorage::new(dir)), "settings")
+}
+
+#[test]
+fn uses_defaults_if_nothing_is_stored() {
+    let dir = storage_dir();
+    let settings = Settings::load(&namespace(dir.path())).unwrap();
+    assert_eq!(settings, Settings::default());
+}
+
+#[test]
+fn keeps_settings_across_restarts() {
+    let dir = storage_dir();
+    let settings = Settings {
+        scan_interval: Duration::from_secs(30),
+        sort: SortKey::Channel,
+        brightness: 25,
+        theme: Theme::Forest,
+        idle_timeout: None,
+        long_press: Duration::from_millis(800),
+    };
+    settings.save(&namespace(dir.path())).unwrap();
+
+    let loaded = Settings::load(&namespace(dir.path())).unwrap();
+    assert_eq!(loaded, settings);
+}
+
+#[test]
+fn loads_settings_of_version_1() {
+    let dir = storage_dir();
+    let handle = namespace(dir.path());
+    // Fields in the order version 1 stores them
+    let stored = (
+        Duration::from_secs(10),
+        SortKey::Ssid,
+        50_u8,
+        Theme::Mono,
+        Some(Duration::from_secs(30)),
+        Duration::from_millis(300),
+    );
+    handle.put("version", &1_u8).unwrap();
+    handle.put("settings", &stored).unwrap();
+    handle.commit().unwrap();
+
+    let settings = Settings::load(&namespace(dir.path())).unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            scan_interval: Duration::from_secs(10),
+            sort: SortKey::Ssid,
+            brightness: 50,
+            theme: Theme::Mono,
+            idle_timeout: Some(Duration::from_secs(30)),
+            long_press: Duration::from_millis(300),
+        }
+    );
+}
+
+/// Store settings as a newer firmware would.
+fn store_newer_version(dir: &Path) {
+    let handle = namespace(dir);
+    handle.put("version", &99_u8).unwrap();
+    handle.put("settings", &[1_u8, 2, 3][..]).unwrap();
+    handle.commit().unwrap();
+}
+
+#[test]
+fn refuses_settings_of_newer_version() {
+    let dir = storage_dir();
+    store_newer_version(dir.path());
+
+    let handle = namespace(dir.path());
+    assert!(Settings::load(&handle).is_err());
+    assert!(Settings::default().save(&handle).is_err());
+
+    let handle = namespace(dir.path());
+    assert_eq!(handle.get::<u8>("version").unwrap(), Some(99));
+    assert_eq!(
+        handle.get::<Vec<u8>>("settings").unwrap(),
+        Some(vec![1, 2, 3])
+    );
+}
+
+#[test]
+fn app_keeps_settings_of_newer_version() {
+    let dir = storage_dir();
+    store_newer_version(dir.path());
+
+    let mut app = AppState::new().with_settings(namespace(dir.path()));
+    assert_eq!(*app.settings(), Settings::default());
+    let mut out = Vec::new();
+    app.run_command(Command::Sort(SortKey::Security), &mut out)
+        .unwrap();
+
+    let handle = namespace(dir.path());
+    assert_eq!(handle.get::<u8>("version").unwrap(), Some(99));
+    assert_eq!(
+        handle.get::<Vec<u8>>("settings").unwrap(),
+        Some(vec![1, 2, 3])
+    );
+}
+
+#[test]
+fn cycles_through_values() {
+    let mut settings = Settings::default();
+    let themes: Vec<Theme> = (0..5)
+        .map(|_| {
+            settings.next_value(Setting::Theme);
+            settings.theme
+        })
+        .collect();
+    assert_eq!(
+        themes,
+        [
+            Theme::Ocean,
+            Theme::Forest,
+            Theme::Mono,
+            Theme::Workshop,
+            Theme::Ocean
+        ]
+    );
+
+    settings.next_value(Setting::IdleTimeout);
+    assert_eq!(settings.value(Setting::IdleTimeout), "120s");
+    settings.next_value(Setting::IdleTimeout);
+    settings.next_value(Setting::IdleTimeout);
+    assert_eq!(settings.value(Setting::IdleTimeout), "Never");
+
+    // Not one of the options, e.g. set from the console
+    settings.scan_interval = Duration::from_secs(7);
+    settings.next_value(Setting::ScanInterval);
+    assert_eq!(settings.scan_interval, Duration::from_secs(10));
+}
+
+#[test]
+fn settings_tab_edits_and_saves_settings() {
+    let dir = storage_dir();
+    let app = AppState::new().with_settings(namespace(dir.path()));
+    let mut harness = Harness::new(app);
+    harness.press(NEXT_TAB).press(NEXT_TAB).press(NEXT_TAB);
+    harness
+        .press(NEXT_SETTING)
+        .press(NEXT_SETTING)
+        .press(NEXT_SETTING)
+        .press(NEXT_VALUE);
+    harness.assert_snapshot("settings_tab_theme");
+
+    let saved = Settings::load(&namespace(dir.path())).unwrap();
+    assert_eq!(saved.theme, Theme::Ocean);
+    assert_eq!(saved, *harness.app().settings());
+}
+
+#[test]
+fn console_changes_are_saved() {
+    let dir = storage_dir();
+    let mut app = AppState::new().with_settings(namespace(dir.path()));
+    let mut out = Vec::new();
+    app.run_command(Command::Sort(SortKey::Security), &mut out)
+        .unwrap();
+    app.run_command(Command::SetInterval(Duration::from_secs(5)), &mut out)
+        .unwrap();
+
+    let app = AppState::new().with_settings(namespace(dir.path()));
+    assert_eq!(app.settings().sort, SortKey::Security);
+    assert_eq!(app.settings().scan_interval, Duration::from_secs(5));
+}
+
+#[test]
+fn long_press_setting_configures_buttons() {
+    let dir = storage_dir();
+    let namespace = namespace(dir.path());
+    let settings = Settings {
+        long_press: Duration::from_millis(800),
+        ..Settings::default()
+    };
+    settings.save(&namespace).unwrap();
+
+    let app = AppState::new().with_settings(namespace);
+    for button in [ButtonId::Button1, ButtonId::Button2] {
+        assert_eq!(
+            app.button_config(button).long_press,
+            Duration::from_millis(800)
+        );
+    }
+}
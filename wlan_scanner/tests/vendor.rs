@@ -0,0 +1,46 @@
+use ratatui_wlan_scanner::vendor::vendor_for;
+use ratatui_wlan_scanner::wifi::Bssid;
+
+#[test]
+fn finds_vendor_by_oui() {
+    assert_eq!(
+        vendor_for(&Bssid([0x24, 0x0A, 0xC4, 0x12, 0x34, 0x56])),
+        Some("Espressif")
+    );
+    assert_eq!(
+        vendor_for(&Bssid([0x00, 0x00, 0x0C, 0x00, 0x00, 0x01])),
+        Some("Cisco Systems")
+    );
+}
+
+#[test]
+fn shortens_vendor_names() {
+    assert_eq!(
+        vendor_for(&Bssid([0x14, 0xCC, 0x20, 0x00, 0x00, 0x01])),
+        Some("TP-LINK")
+    );
+    assert_eq!(
+        vendor_for(&Bssid([0x00, 0x0F, 0x66, 0x00, 0x00, 0x01])),
+        Some("Cisco-Linksys")
+    );
+}
+
+#[test]
+fn unknown_oui_has_no_vendor() {
+    assert_eq!(
+        vendor_for(&Bssid([0x00, 0x00, 0x00, 0x00, 0x00, 0x01])),
+        None
+    );
+    assert_eq!(
+        vendor_for(&Bssid([0xFC, 0xFF, 0xFF, 0x00, 0x00, 0x01])),
+        None
+    );
+}
+
+#[test]
+fn locally_administered_address_has_no_vendor() {
+    assert_eq!(
+        vendor_for(&Bssid([0x02, 0x00, 0x5E, 0x10, 0x00, 0x01])),
+        None
+    );
+}